@@ -1,17 +1,15 @@
 extern crate semiavidpr;
-use semiavidpr::{SemiAvidPr};
+use semiavidpr::{SemiAvidPr, FileMatrix, Curve, CurveVisitor, NamedCurve};
 
-use ark_ec::{PairingEngine};
-use ark_bls12_381::{Bls12_381};
-use ark_bn254::{Bn254};
+use ark_std::{UniformRand};
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
 
 
-fn bench_disperse_compute_column_commitments<E: PairingEngine + CurveName>(c: &mut Criterion) {
+fn bench_disperse_compute_column_commitments<E: NamedCurve>(c: &mut Criterion) {
     let mut rng = ark_std::rand::thread_rng();
 
-    let mut group = c.benchmark_group(format!("{}/disperse_compute_column_commitments", E::curve_name()));
+    let mut group = c.benchmark_group(format!("{}/disperse_compute_column_commitments", E::NAME));
     group.sample_size(10);
 
     let n = 1024;
@@ -33,10 +31,10 @@ fn bench_disperse_compute_column_commitments<E: PairingEngine + CurveName>(c: &m
 }
 
 
-fn bench_disperse_encode_rows<E: PairingEngine + CurveName>(c: &mut Criterion) {
+fn bench_disperse_encode_rows<E: NamedCurve>(c: &mut Criterion) {
     let mut rng = ark_std::rand::thread_rng();
 
-    let mut group = c.benchmark_group(format!("{}/disperse_encode_rows", E::curve_name()));
+    let mut group = c.benchmark_group(format!("{}/disperse_encode_rows", E::NAME));
     group.sample_size(10);
 
     for n in vec![256, 512, 1024, 2048] {
@@ -59,33 +57,86 @@ fn bench_disperse_encode_rows<E: PairingEngine + CurveName>(c: &mut Criterion) {
 }
 
 
-pub trait CurveName {
-    fn curve_name() -> &'static str;
+fn bench_file_layout<E: NamedCurve>(c: &mut Criterion) {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let mut group = c.benchmark_group(format!("{}/file_layout", E::NAME));
+    group.sample_size(10);
+
+    let k = 341;
+    #[allow(non_snake_case)]
+    let L = 4096;
+    let file_nested: Vec<Vec<E::Fr>> = (0..L).map(|_| (0..k).map(|_| E::Fr::rand(&mut rng)).collect()).collect();
+    let file_matrix = FileMatrix::from_rows(L, k, &file_nested);
+
+    group.bench_function(format!("columns nested k={} L={}", k, L), |b| {
+        b.iter(|| {
+            for i in 0..k {
+                black_box(file_nested.iter().map(|r| r[i]).collect::<Vec<E::Fr>>());
+            }
+        })
+    });
+    group.bench_function(format!("columns FileMatrix k={} L={}", k, L), |b| {
+        b.iter(|| {
+            for column in file_matrix.columns() {
+                black_box(column.to_vec());
+            }
+        })
+    });
+    group.bench_function(format!("rows nested k={} L={}", k, L), |b| {
+        b.iter(|| {
+            for row in file_nested.iter() {
+                black_box(row.to_vec());
+            }
+        })
+    });
+    group.bench_function(format!("rows FileMatrix k={} L={}", k, L), |b| {
+        b.iter(|| {
+            let file_transposed = file_matrix.transpose();
+            for row in file_transposed.columns() {
+                black_box(row.to_vec());
+            }
+        })
+    });
+
+    group.finish();
 }
 
-impl CurveName for Bls12_381 {
-    fn curve_name() -> &'static str {
-        "Bls12_381"
-    }
+
+/// All benchmarks over the curve it visits.
+struct Benchmarks<'c> {
+    c: &'c mut Criterion,
 }
 
-impl CurveName for Bn254 {
-    fn curve_name() -> &'static str {
-        "Bn254"
+impl CurveVisitor for Benchmarks<'_> {
+    type Output = ();
+
+    fn visit<E: NamedCurve>(self) {
+        bench_disperse_compute_column_commitments::<E>(self.c);
+        bench_disperse_encode_rows::<E>(self.c);
+        bench_file_layout::<E>(self.c);
     }
 }
 
 
-fn bench_bls12_381(c: &mut Criterion) {
-    bench_disperse_compute_column_commitments::<Bls12_381>(c);
-    bench_disperse_encode_rows::<Bls12_381>(c);
+/// Curves to benchmark, as comma-separated names (or `all`) in this environment variable.
+const BENCH_CURVES_VAR: &str = "SEMIAVIDPR_BENCH_CURVES";
+const BENCH_CURVES_DEFAULT: &[Curve] = &[Curve::Bls12_381, Curve::Bn254];
+
+fn bench_curves() -> Vec<Curve> {
+    match std::env::var(BENCH_CURVES_VAR) {
+        Ok(names) if names == "all" => Curve::ALL.to_vec(),
+        Ok(names) => names.split(',').map(|name| name.trim().parse().unwrap_or_else(|err| panic!("{}: {}", BENCH_CURVES_VAR, err))).collect(),
+        Err(_) => BENCH_CURVES_DEFAULT.to_vec(),
+    }
 }
 
-fn bench_bn254(c: &mut Criterion) {
-    bench_disperse_compute_column_commitments::<Bn254>(c);
-    bench_disperse_encode_rows::<Bn254>(c);
+fn bench_selected_curves(c: &mut Criterion) {
+    for curve in bench_curves() {
+        curve.visit(Benchmarks { c });
+    }
 }
 
 
-criterion_group!(benches, bench_bls12_381, bench_bn254);
+criterion_group!(benches, bench_selected_curves);
 criterion_main!(benches);
@@ -4,7 +4,7 @@ cargo build
 cargo build --release
 
 for RATE in "25" "33" "45"; do
-    echo "args_n args_k args_L args_iterations args_curve  net_file_size_bytes  runtime_setup_seconds runtime_file_generation_seconds  runtime_all_column_commitments_seconds runtime_per_column_commitment_seconds runtime_all_row_encodings_seconds runtime_per_row_encoding_seconds runtime_all_chunk_verifications_seconds runtime_per_chunk_verification_seconds  runtime_all_downloaded_chunk_verifications_seconds runtime_per_downloaded_chunk_verification_seconds runtime_all_row_decodings_seconds runtime_per_row_decoding_seconds runtime_prepare_decoding_seconds  num_column_commitments num_row_encodings num_chunk_verifications num_downloaded_chunk_verifications num_row_decodings  size_file_uncoded_bytes size_column_commitments_bytes size_file_coded_bytes  scenario_disperse_runtime_client_seconds scenario_disperse_runtime_node_seconds scenario_disperse_communication_bytes scenario_disperse_storage_bytes  scenario_retrieve_runtime_client_seconds  scenario_sampling_num_openings scenario_sampling_runtime_prover_seconds scenario_sampling_runtime_verifier_seconds scenario_sampling_runtime_proof_size_bytes   ### columns" > data-experiments-RAW-${RATE}.txt
+    echo "args_n args_k args_L args_iterations args_curve args_backend  net_file_size_bytes  runtime_setup_seconds runtime_file_generation_seconds  runtime_all_column_commitments_seconds runtime_per_column_commitment_seconds runtime_all_row_encodings_seconds runtime_per_row_encoding_seconds runtime_all_chunk_verifications_seconds runtime_per_chunk_verification_seconds  runtime_all_downloaded_chunk_verifications_seconds runtime_per_downloaded_chunk_verification_seconds runtime_all_row_decodings_seconds runtime_per_row_decoding_seconds runtime_prepare_decoding_seconds runtime_prepare_decoding_cached_seconds num_decoder_cache_hits num_decoder_cache_misses runtime_check_decoded_recommit_seconds runtime_check_decoded_rlc_seconds  num_column_commitments num_row_encodings num_chunk_verifications num_downloaded_chunk_verifications num_row_decodings  size_file_uncoded_bytes size_column_commitments_bytes size_file_coded_bytes  scenario_disperse_runtime_client_seconds scenario_disperse_runtime_node_seconds scenario_disperse_communication_bytes scenario_disperse_storage_bytes  scenario_retrieve_runtime_client_seconds  scenario_sampling_num_openings scenario_sampling_runtime_prover_seconds scenario_sampling_runtime_verifier_seconds scenario_sampling_runtime_proof_size_bytes  scenario_repair_rs_num_downloaded_chunks scenario_repair_rs_communication_bytes scenario_repair_rs_runtime_seconds scenario_repair_lrc_num_downloaded_chunks scenario_repair_lrc_communication_bytes scenario_repair_lrc_runtime_seconds scenario_repair_lrc_storage_overhead_bytes  scenario_audit_num_audits scenario_audit_num_failed scenario_audit_runtime_prover_seconds scenario_audit_runtime_verifier_seconds scenario_audit_response_size_bytes  scenario_network_dispersal_latency_seconds scenario_network_retrieval_latency_seconds scenario_network_sampling_latency_seconds scenario_network_client_sent_bytes scenario_network_max_node_cpu_seconds  scenario_certificate_quorum scenario_certificate_runtime_sign_seconds scenario_certificate_runtime_aggregate_seconds scenario_certificate_runtime_verify_seconds scenario_certificate_size_bytes   ### columns" > data-experiments-RAW-${RATE}.txt
 done
 
 
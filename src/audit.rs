@@ -0,0 +1,199 @@
+use ark_ec::{PairingEngine};
+use ark_poly::{EvaluationDomain};
+use ark_std::{UniformRand, start_timer, end_timer};
+
+use rand::{Rng};
+use rand::seq::{index};
+
+use crate::{SemiAvidPr, ColumnCommitmentScheme, FileMatrix};
+
+
+/// Challenge of a storage audit, sent by the auditor to a storage node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuditChallenge<F> {
+    /// Open the given rows of the node's chunk.
+    Rows(Vec<usize>),
+    /// Evaluate the polynomial of every segment of the node's chunk at the given (random) point.
+    Point(F),
+}
+
+
+/// Response of a storage node to an `AuditChallenge`: one value and one opening proof per
+/// opened row (for `Rows`) or per segment (for `Point`), against the node's coded chunk
+/// commitments.
+#[derive(Clone, Debug)]
+pub struct AuditResponse<F, P> {
+    pub values: Vec<F>,
+    pub proofs: Vec<P>,
+}
+
+
+/// Error for challenges that a node cannot answer since they open row `row`, beyond its chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidChallenge {
+    pub row: usize,
+}
+
+impl core::fmt::Display for InvalidChallenge {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "challenge opens row {} beyond the chunk", self.row)
+    }
+}
+
+impl std::error::Error for InvalidChallenge {}
+
+
+/// Outcomes of the audits of one storage node (or of all nodes, see `AuditScheduler::total`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AuditStats {
+    pub num_audits: usize,
+    pub num_passed: usize,
+    pub num_failed: usize,
+}
+
+
+/// Round-robin schedule of audits of `nodes_per_round` out of the `n` storage nodes per round,
+/// keeping per-node statistics.
+#[derive(Clone, Debug)]
+pub struct AuditScheduler {
+    nodes_per_round: usize,
+    next: usize,
+    stats: Vec<AuditStats>,
+}
+
+impl AuditScheduler {
+    pub fn new(n: usize, nodes_per_round: usize) -> Self {
+        assert!(0 < nodes_per_round && nodes_per_round <= n);
+        AuditScheduler { nodes_per_round, next: 0, stats: vec![AuditStats::default(); n] }
+    }
+
+    /// The nodes to audit in the next round, continuing where the previous round stopped.
+    pub fn next_round(&mut self) -> Vec<usize> {
+        let n = self.stats.len();
+        let idxs = (0..self.nodes_per_round).map(|i| (self.next + i) % n).collect();
+        self.next = (self.next + self.nodes_per_round) % n;
+        idxs
+    }
+
+    pub fn record(&mut self, idx: usize, passed: bool) {
+        let stats = &mut self.stats[idx];
+        stats.num_audits += 1;
+        if passed {
+            stats.num_passed += 1;
+        } else {
+            stats.num_failed += 1;
+        }
+    }
+
+    pub fn stats(&self, idx: usize) -> AuditStats {
+        self.stats[idx]
+    }
+
+    pub fn total(&self) -> AuditStats {
+        self.stats.iter().fold(AuditStats::default(), |acc, s| AuditStats {
+            num_audits: acc.num_audits + s.num_audits,
+            num_passed: acc.num_passed + s.num_passed,
+            num_failed: acc.num_failed + s.num_failed,
+        })
+    }
+
+    /// Nodes that failed at least one audit.
+    pub fn failing_nodes(&self) -> Vec<usize> {
+        (0..self.stats.len()).filter(|&i| self.stats[i].num_failed > 0).collect()
+    }
+}
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
+    /// Challenge a node to open `num_rows` distinct random rows of its chunk.
+    pub fn audit_challenge_rows<R: Rng + ?Sized>(&self, rng: &mut R, num_rows: usize) -> AuditChallenge<E::Fr> {
+        AuditChallenge::Rows(index::sample(rng, self.get_num_rows(), num_rows).into_vec())
+    }
+
+    /// Challenge a node to evaluate its chunk polynomials at a random point.
+    pub fn audit_challenge_point<R: Rng + ?Sized>(&self, mut rng: &mut R) -> AuditChallenge<E::Fr> {
+        AuditChallenge::Point(E::Fr::rand(&mut rng))
+    }
+
+
+    /// Answer `challenge` from the stored `chunk` (of length `get_num_rows()`), unless it
+    /// opens rows beyond the chunk.
+    pub fn audit_respond(&self, chunk: &[E::Fr], challenge: &AuditChallenge<E::Fr>) -> Result<AuditResponse<E::Fr, C::Proof>, InvalidChallenge> {
+        assert!(chunk.len() == self.get_num_rows());
+        if let AuditChallenge::Rows(rows) = challenge {
+            if let Some(&row) = rows.iter().find(|&&row| row >= self.get_num_rows()) {
+                return Err(InvalidChallenge { row });
+            }
+        }
+
+        let timer = start_timer!(|| "Responding to audit challenge");
+        let (values, proofs) = match challenge {
+            AuditChallenge::Rows(rows) => rows.iter().map(|&row| {
+                let (segment, row_in_segment) = (row / self.L, row % self.L);
+                self.commitment_scheme.open(&chunk[segment*self.L..(segment+1)*self.L], self.domain_polycommit.element(row_in_segment))
+            }).unzip(),
+            AuditChallenge::Point(point) => (0..self.num_segments).map(|s| {
+                self.commitment_scheme.open(&chunk[s*self.L..(s+1)*self.L], *point)
+            }).unzip(),
+        };
+        end_timer!(timer);
+
+        Ok(AuditResponse { values, proofs })
+    }
+
+
+    /// Check the response of node `idx` to `challenge` against its coded chunk commitments,
+    /// derived from the column commitments.
+    pub fn audit_verify(&self, column_commitments: &[C::Commitment], idx: usize, challenge: &AuditChallenge<E::Fr>, response: &AuditResponse<E::Fr, C::Proof>) -> bool {
+        if idx >= self.n || response.values.len() != response.proofs.len() {
+            return false;
+        }
+
+        let timer = start_timer!(|| "Verifying audit response");
+        let coded_chunk_commitments: Vec<C::Commitment> = (0..self.num_segments).map(|s| self.encode_commitments(column_commitments, s, idx)).collect();
+        let ret_val = match challenge {
+            AuditChallenge::Rows(rows) => {
+                rows.len() == response.values.len()
+                    && rows.iter().zip(response.values.iter().zip(response.proofs.iter())).all(|(&row, (value, proof))| {
+                        let (segment, row_in_segment) = (row / self.L, row % self.L);
+                        row < self.get_num_rows() && self.commitment_scheme.verify(&coded_chunk_commitments[segment], self.domain_polycommit.element(row_in_segment), *value, proof)
+                    })
+            },
+            AuditChallenge::Point(point) => {
+                self.num_segments == response.values.len()
+                    && response.values.iter().zip(response.proofs.iter()).zip(coded_chunk_commitments.iter()).all(|((value, proof), commitment)| {
+                        self.commitment_scheme.verify(commitment, *point, *value, proof)
+                    })
+            },
+        };
+        end_timer!(timer);
+
+        ret_val
+    }
+
+
+    /// Simulate `num_rounds` rounds of audits of the nodes chosen by `scheduler` against the
+    /// chunks the nodes actually store (columns of `data_stored`, which may deviate from the
+    /// dispersed ones). Challenges open `num_challenge_rows` random rows, or evaluate at a
+    /// random point if `num_challenge_rows` is `None`.
+    pub fn audit_simulate<R: Rng + ?Sized>(&self, rng: &mut R, column_commitments: &[C::Commitment], data_stored: &FileMatrix<E::Fr>, scheduler: &mut AuditScheduler, num_rounds: usize, num_challenge_rows: Option<usize>) -> AuditStats {
+        assert!(data_stored.width() == self.n);
+
+        let timer_outer = start_timer!(|| "Simulating audits");
+        for _ in 0..num_rounds {
+            let timer_inner = start_timer!(|| "Audit round");
+            for idx in scheduler.next_round() {
+                let challenge = match num_challenge_rows {
+                    Some(num_rows) => self.audit_challenge_rows(rng, num_rows),
+                    None => self.audit_challenge_point(rng),
+                };
+                let response = self.audit_respond(data_stored.column(idx), &challenge).unwrap();
+                scheduler.record(idx, self.audit_verify(column_commitments, idx, &challenge, &response));
+            }
+            end_timer!(timer_inner);
+        }
+        end_timer!(timer_outer);
+
+        scheduler.total()
+    }
+}
@@ -0,0 +1,95 @@
+use ark_ff::fields::{Field};
+
+use std::collections::{HashMap, HashSet};
+
+use crate::utils::{Matrix};
+
+
+/// Bounded least-recently-used cache of decoding matrices, keyed by the sorted set of indices
+/// of the nodes that chunks were downloaded from.
+#[derive(Clone, Debug)]
+pub struct DecoderCache<F: Field> {
+    capacity: usize,
+    entries: HashMap<Vec<usize>, (Matrix<F>, u64)>,
+    clock: u64,
+    num_hits: usize,
+    num_misses: usize,
+}
+
+impl<F: Field> DecoderCache<F> {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, entries: HashMap::new(), clock: 0, num_hits: 0, num_misses: 0 }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get_num_hits(&self) -> usize {
+        self.num_hits
+    }
+
+    pub fn get_num_misses(&self) -> usize {
+        self.num_misses
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > self.capacity {
+            self.evict();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn get(&mut self, key: &[usize]) -> Option<Matrix<F>> {
+        self.clock += 1;
+        match self.entries.get_mut(key) {
+            Some((matrix, last_used)) => {
+                *last_used = self.clock;
+                self.num_hits += 1;
+                Some(matrix.clone())
+            },
+            None => {
+                self.num_misses += 1;
+                None
+            },
+        }
+    }
+
+    /// Most recently used key that only contains elements of `idxs`, without counting as a use.
+    pub fn find_key_within(&self, idxs: &HashSet<usize>) -> Option<Vec<usize>> {
+        self.entries.iter()
+            .filter(|(key, _)| key.iter().all(|i| idxs.contains(i)))
+            .max_by_key(|(_, (_, last_used))| *last_used)
+            .map(|(key, _)| key.clone())
+    }
+
+    pub fn insert(&mut self, key: Vec<usize>, matrix: Matrix<F>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        self.entries.insert(key, (matrix, self.clock));
+    }
+
+    fn evict(&mut self) {
+        let key_lru = self.entries.iter().min_by_key(|(_, (_, last_used))| *last_used).map(|(key, _)| key.clone());
+        if let Some(key) = key_lru {
+            self.entries.remove(&key);
+        }
+    }
+}
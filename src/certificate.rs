@@ -0,0 +1,241 @@
+use ark_ec::{AffineCurve, ProjectiveCurve, PairingEngine};
+use ark_ff::fields::{PrimeField};
+use ark_serialize::{CanonicalSerialize, CanonicalDeserialize, Read, Write, SerializationError};
+use ark_std::{Zero, One, UniformRand, start_timer, end_timer};
+
+use blake2::{Blake2b, Digest};
+
+use rand::{Rng};
+
+use crate::{SemiAvidPr, ColumnCommitmentScheme};
+use crate::pedersen::{hash_to_curve};
+
+
+/// Domain separators of the hashes to G1, so that a signature on a file is never one on a
+/// public key or vice versa.
+const FILE_ID_DOMAIN: &[u8] = b"semiavidpr-file-id";
+const CHUNK_ID_DOMAIN: &[u8] = b"semiavidpr-chunk-id";
+const SIGNATURE_DOMAIN: &[u8] = b"semiavidpr-bls-signature";
+const CHUNK_SIGNATURE_DOMAIN: &[u8] = b"semiavidpr-bls-chunk-signature";
+const POSSESSION_DOMAIN: &[u8] = b"semiavidpr-bls-possession";
+
+
+/// Identifier of a dispersed file, a hash of the code parameters and the column commitments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FileId(pub [u8; 64]);
+
+/// Identifier of the chunk that a node returns for a file, a hash of the file identifier, the
+/// node index and the chunk. Two different ones signed by the same node for the same file prove
+/// that it equivocated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChunkId(pub [u8; 64]);
+
+
+/// BLS signing key of a storage node.
+#[derive(Clone)]
+pub struct SigningKey<E: PairingEngine>(E::Fr);
+
+/// BLS public key, in G2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PublicKey<E: PairingEngine>(E::G2Affine);
+
+/// BLS signature, in G1, on a file identifier or (as proof of possession) on a public key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Signature<E: PairingEngine>(E::G1Affine);
+
+
+/// `e(signature, g2) == e(message_point, public_key)`
+fn check_signature<E: PairingEngine>(signature: E::G1Affine, message_point: E::G1Affine, public_key: E::G2Affine) -> bool {
+    E::product_of_pairings(&[
+        (signature.into(), (-E::G2Affine::prime_subgroup_generator()).into()),
+        (message_point.into(), public_key.into()),
+    ]).is_one()
+}
+
+fn possession_point<E: PairingEngine>(public_key: &PublicKey<E>) -> E::G1Affine {
+    let mut bytes = Vec::new();
+    public_key.serialize(&mut bytes).unwrap();
+    hash_to_curve(POSSESSION_DOMAIN, &bytes)
+}
+
+
+impl<E: PairingEngine> SigningKey<E> {
+    pub fn generate<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self(E::Fr::rand(rng))
+    }
+
+    pub fn public_key(&self) -> PublicKey<E> {
+        PublicKey(E::G2Affine::prime_subgroup_generator().mul(self.0.into_repr()).into_affine())
+    }
+
+    pub fn sign(&self, file_id: &FileId) -> Signature<E> {
+        Signature(hash_to_curve::<E::G1Affine>(SIGNATURE_DOMAIN, &file_id.0).mul(self.0.into_repr()).into_affine())
+    }
+
+    /// Signature on a returned chunk, which makes the node accountable for it.
+    pub fn sign_chunk(&self, chunk_id: &ChunkId) -> Signature<E> {
+        Signature(hash_to_curve::<E::G1Affine>(CHUNK_SIGNATURE_DOMAIN, &chunk_id.0).mul(self.0.into_repr()).into_affine())
+    }
+
+    /// Signature on the own public key, which the registry requires against rogue-key attacks
+    /// on aggregate signatures.
+    pub fn prove_possession(&self) -> Signature<E> {
+        Signature(possession_point(&self.public_key()).mul(self.0.into_repr()).into_affine())
+    }
+}
+
+
+impl<E: PairingEngine> PublicKey<E> {
+    pub fn verify(&self, file_id: &FileId, signature: &Signature<E>) -> bool {
+        check_signature::<E>(signature.0, hash_to_curve(SIGNATURE_DOMAIN, &file_id.0), self.0)
+    }
+
+    pub fn verify_chunk(&self, chunk_id: &ChunkId, signature: &Signature<E>) -> bool {
+        check_signature::<E>(signature.0, hash_to_curve(CHUNK_SIGNATURE_DOMAIN, &chunk_id.0), self.0)
+    }
+
+    pub fn verify_possession(&self, proof_of_possession: &Signature<E>) -> bool {
+        !self.0.is_zero() && check_signature::<E>(proof_of_possession.0, possession_point(self), self.0)
+    }
+}
+
+
+/// Public keys of the storage nodes, node `idx` being the `idx`-th to register.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeRegistry<E: PairingEngine> {
+    public_keys: Vec<PublicKey<E>>,
+}
+
+impl<E: PairingEngine> NodeRegistry<E> {
+    pub fn new() -> Self {
+        Self { public_keys: Vec::new() }
+    }
+
+    /// Register the next node, returning its index, if `proof_of_possession` is valid.
+    pub fn register(&mut self, public_key: PublicKey<E>, proof_of_possession: &Signature<E>) -> Option<usize> {
+        if !public_key.verify_possession(proof_of_possession) {
+            return None;
+        }
+        self.public_keys.push(public_key);
+        Some(self.public_keys.len() - 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.public_keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.public_keys.is_empty()
+    }
+
+    pub fn public_key(&self, idx: usize) -> &PublicKey<E> {
+        &self.public_keys[idx]
+    }
+}
+
+impl<E: PairingEngine> Default for NodeRegistry<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+/// Aggregate signature of at least a quorum of nodes on a file identifier, attesting that
+/// they verified and stored their chunks. Its size is one G1 element plus one bit per
+/// registered node.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DispersalCertificate<E: PairingEngine> {
+    /// Bitmap of the signers, node `idx` at bit `idx % 8` of byte `idx / 8`.
+    signers: Vec<u8>,
+    signature: Signature<E>,
+}
+
+impl<E: PairingEngine> DispersalCertificate<E> {
+    /// Aggregate the valid ones of `signatures` (by node index) on `file_id`, if there are at
+    /// least `quorum` of them. Invalid and duplicate signatures are skipped.
+    pub fn aggregate(registry: &NodeRegistry<E>, file_id: &FileId, signatures: &[(usize, Signature<E>)], quorum: usize) -> Option<Self> {
+        let timer = start_timer!(|| "Aggregating dispersal certificate");
+        let mut signers = vec![0u8; registry.len().div_ceil(8)];
+        let mut num_signers = 0;
+        let mut signature = E::G1Projective::zero();
+        for (idx, s) in signatures.iter() {
+            if *idx >= registry.len() || signers[idx / 8] & (1 << (idx % 8)) != 0 || !registry.public_key(*idx).verify(file_id, s) {
+                continue;
+            }
+            signers[idx / 8] |= 1 << (idx % 8);
+            num_signers += 1;
+            signature.add_assign_mixed(&s.0);
+        }
+        end_timer!(timer);
+
+        if num_signers < quorum {
+            return None;
+        }
+        Some(Self { signers, signature: Signature(signature.into_affine()) })
+    }
+
+    pub fn signers(&self) -> Vec<usize> {
+        (0..self.signers.len() * 8).filter(|idx| self.signers[idx / 8] & (1 << (idx % 8)) != 0).collect()
+    }
+
+    /// Check that at least `quorum` nodes of `registry` signed `file_id`.
+    pub fn verify(&self, registry: &NodeRegistry<E>, file_id: &FileId, quorum: usize) -> bool {
+        let signers = self.signers();
+        if self.signers.len() != registry.len().div_ceil(8) || signers.iter().any(|&idx| idx >= registry.len()) || signers.is_empty() || signers.len() < quorum {
+            return false;
+        }
+
+        let timer = start_timer!(|| "Verifying dispersal certificate");
+        let public_key = signers.iter().fold(E::G2Projective::zero(), |mut acc, &idx| {
+            acc.add_assign_mixed(&registry.public_key(idx).0);
+            acc
+        });
+        let ret_val = PublicKey::<E>(public_key.into_affine()).verify(file_id, &self.signature);
+        end_timer!(timer);
+
+        ret_val
+    }
+}
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
+    pub fn file_id(&self, column_commitments: &[C::Commitment]) -> FileId {
+        let mut bytes = Vec::new();
+        for x in [self.n, self.k, self.L, self.num_segments] {
+            bytes.extend_from_slice(&(x as u64).to_le_bytes());
+        }
+        for commitment in column_commitments.iter() {
+            commitment.serialize(&mut bytes).unwrap();
+        }
+
+        let mut file_id = [0u8; 64];
+        file_id.copy_from_slice(&Blake2b::new().chain(FILE_ID_DOMAIN).chain(&bytes).finalize());
+        FileId(file_id)
+    }
+
+    pub fn chunk_id(&self, file_id: &FileId, chunk: &[E::Fr], idx: usize) -> ChunkId {
+        let mut bytes = (idx as u64).to_le_bytes().to_vec();
+        for entry in chunk.iter() {
+            entry.serialize(&mut bytes).unwrap();
+        }
+
+        let mut chunk_id = [0u8; 64];
+        chunk_id.copy_from_slice(&Blake2b::new().chain(CHUNK_ID_DOMAIN).chain(file_id.0).chain(&bytes).finalize());
+        ChunkId(chunk_id)
+    }
+
+    /// Signatures needed for a certificate when up to `num_faulty` nodes are Byzantine: with
+    /// `n - num_faulty`, at least `k` of the signers are honest and hold valid chunks.
+    pub fn certificate_quorum(&self, num_faulty: usize) -> usize {
+        assert!(self.k + 2*num_faulty <= self.n, "Too many faulty nodes for a retrievability certificate");
+        self.n - num_faulty
+    }
+
+    /// Sign the file identifier as node `idx`, if the chunk is valid.
+    pub fn disperse_sign_chunk(&self, signing_key: &SigningKey<E>, column_commitments: &[C::Commitment], chunk: &[E::Fr], idx: usize) -> Option<Signature<E>> {
+        if !self.disperse_verify_chunk(column_commitments, chunk, idx) {
+            return None;
+        }
+        Some(signing_key.sign(&self.file_id(column_commitments)))
+    }
+}
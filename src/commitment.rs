@@ -0,0 +1,50 @@
+use ark_ff::fields::{PrimeField};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::{CanonicalSerialize};
+
+use rand::{Rng};
+
+use std::fmt::{Debug};
+
+
+/// A linear commitment scheme for the columns of a file, where a column (segment) of `L`
+/// entries is read as the evaluations of a polynomial of degree less than `L` over the domain
+/// of size `L` passed to `setup`.
+///
+/// Linearity is what lets anyone compute the commitments to the coded chunks from the column
+/// commitments (see `encode`), and is all that the protocol logic of `SemiAvidPr` relies on.
+pub trait ColumnCommitmentScheme<F: PrimeField>: Sized {
+    type Commitment: Copy + Debug + PartialEq + Eq + CanonicalSerialize;
+    type Proof: Clone + Debug;
+
+    fn setup<R: Rng + ?Sized>(rng: &mut R, domain: GeneralEvaluationDomain<F>) -> Self;
+
+    /// Commitment to the column with entries `evals`.
+    fn commit(&self, evals: &[F]) -> Self::Commitment;
+
+    /// Commitment to `sum_i coeffs[i] * column_i`, from the commitments to the `column_i`.
+    fn combine(&self, commitments: &[Self::Commitment], coeffs: &[F]) -> Self::Commitment;
+
+    /// Commitments to the Reed-Solomon encoding over `domain` of the committed columns, i.e.,
+    /// for every point `x` of `domain`, to `sum_i x^i * column_i`.
+    fn encode(&self, commitments: &[Self::Commitment], domain: GeneralEvaluationDomain<F>) -> Vec<Self::Commitment> {
+        domain.elements().map(|x| {
+            let coeffs: Vec<F> = (0..commitments.len() as u64).map(|i| x.pow([i])).collect();
+            self.combine(commitments, &coeffs)
+        }).collect()
+    }
+
+    /// Value at `point` of the polynomial with evaluations `evals`, and a proof thereof.
+    fn open(&self, evals: &[F], point: F) -> (F, Self::Proof);
+
+    fn verify(&self, commitment: &Self::Commitment, point: F, value: F, proof: &Self::Proof) -> bool;
+
+    /// Size of a proof on the wire (compressed group elements), in bytes.
+    fn proof_size(proof: &Self::Proof) -> usize;
+
+    /// Check several openings at once, possibly cheaper than one by one.
+    fn batch_verify<R: Rng + ?Sized>(&self, _rng: &mut R, commitments: &[Self::Commitment], points: &[F], values: &[F], proofs: &[Self::Proof]) -> bool {
+        commitments.len() == points.len() && points.len() == values.len() && values.len() == proofs.len()
+            && (0..commitments.len()).all(|i| self.verify(&commitments[i], points[i], values[i], &proofs[i]))
+    }
+}
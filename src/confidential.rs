@@ -0,0 +1,78 @@
+use ark_ec::{PairingEngine};
+use ark_std::{UniformRand, start_timer, end_timer};
+
+use rand::{Rng};
+
+use std::ops::{Range};
+
+use crate::{SemiAvidPr, ColumnCommitmentScheme, FileMatrix};
+
+
+/// Ramp secret sharing layout: the `k + z` columns of the file that the scheme disperses are
+/// the `k` columns of the payload followed by `z` uniformly random columns, i.e., the
+/// coefficients of the top `z` powers of every row polynomial. Any `z` chunks are then
+/// uniformly random and independent of the payload, any `k + z` chunks decode it, and in
+/// between the nodes learn only part of it. The scheme is set up with `k + z` columns.
+///
+/// Column commitments to the masked file should be hiding (see
+/// `disperse_compute_column_commitments_hiding`), and openings of payload columns reveal the
+/// opened entries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfidentialLayout {
+    k: usize,
+    z: usize,
+}
+
+impl ConfidentialLayout {
+    pub fn new(k: usize, z: usize) -> Self {
+        assert!(k >= 1 && z >= 1);
+        Self { k, z }
+    }
+
+    pub fn num_payload_columns(&self) -> usize {
+        self.k
+    }
+
+    pub fn num_random_columns(&self) -> usize {
+        self.z
+    }
+
+    /// Number of columns of the masked file, and number of chunks needed for retrieval.
+    pub fn code_dimension(&self) -> usize {
+        self.k + self.z
+    }
+
+    pub fn random_columns(&self) -> Range<usize> {
+        self.k..self.k + self.z
+    }
+}
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
+    /// Append `z` random columns to the payload `data_payload` (of `k` columns), to be
+    /// dispersed in its place.
+    pub fn confidential_mask_file<R: Rng + ?Sized>(&self, mut rng: &mut R, layout: &ConfidentialLayout, data_payload: &FileMatrix<E::Fr>) -> FileMatrix<E::Fr> {
+        assert!(layout.code_dimension() == self.k);
+        assert!(data_payload.height() == self.get_num_rows() && data_payload.width() == layout.num_payload_columns());
+
+        let timer = start_timer!(|| "Masking file with random columns");
+        let height = data_payload.height();
+        let columns = data_payload.columns().map(|c| c.to_vec())
+            .chain(layout.random_columns().map(|_| (0..height).map(|_| E::Fr::rand(&mut rng)).collect()))
+            .collect();
+        let data_masked = FileMatrix::from_columns(height, self.k, columns);
+        end_timer!(timer);
+
+        data_masked
+    }
+
+
+    /// Drop the random columns from the output of `retrieve_decode_rows` (from `k + z` chunks).
+    pub fn confidential_unmask_file(&self, layout: &ConfidentialLayout, data_decoded: &FileMatrix<E::Fr>) -> FileMatrix<E::Fr> {
+        assert!(layout.code_dimension() == self.k);
+        assert!(data_decoded.width() == self.k);
+
+        let columns = data_decoded.columns().take(layout.num_payload_columns()).map(|c| c.to_vec()).collect();
+        FileMatrix::from_columns(data_decoded.height(), layout.num_payload_columns(), columns)
+    }
+}
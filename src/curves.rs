@@ -0,0 +1,75 @@
+use ark_ec::{PairingEngine};
+
+
+/// Pairing-friendly curve known by name, e.g., for benchmark labels.
+pub trait NamedCurve: PairingEngine {
+    const NAME: &'static str;
+}
+
+
+/// Code to run over a curve chosen at runtime, see `Curve::visit`.
+pub trait CurveVisitor {
+    type Output;
+
+    fn visit<E: NamedCurve>(self) -> Self::Output;
+}
+
+
+/// Registry of the curves that the experiment binary, benchmarks and tests run over. Adding a
+/// curve takes its arkworks crate in `Cargo.toml` and one line in the invocation below.
+macro_rules! curves {
+    ($($variant:ident => $engine:ty, $name:literal;)*) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+        pub enum Curve {
+            $($variant,)*
+        }
+
+        impl Curve {
+            pub const ALL: &'static [Curve] = &[$(Curve::$variant,)*];
+            pub const NAMES: &'static [&'static str] = &[$($name,)*];
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Curve::$variant => $name,)*
+                }
+            }
+
+            /// Run `visitor` with the pairing engine of this curve.
+            pub fn visit<V: CurveVisitor>(&self, visitor: V) -> V::Output {
+                match self {
+                    $(Curve::$variant => visitor.visit::<$engine>(),)*
+                }
+            }
+        }
+
+        $(impl NamedCurve for $engine {
+            const NAME: &'static str = $name;
+        })*
+    };
+}
+
+curves! {
+    Bls12_381 => ark_bls12_381::Bls12_381, "bls12-381";
+    Bn254 => ark_bn254::Bn254, "bn254";
+    Bls12_377 => ark_bls12_377::Bls12_377, "bls12-377";
+    Bw6_761 => ark_bw6_761::BW6_761, "bw6-761";
+    Mnt4_298 => ark_mnt4_298::MNT4_298, "mnt4-298";
+    Mnt6_298 => ark_mnt6_298::MNT6_298, "mnt6-298";
+}
+
+
+impl std::fmt::Display for Curve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for Curve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Curve::ALL.iter().copied()
+            .find(|c| c.name() == s)
+            .ok_or_else(|| format!("unknown curve '{}', expected one of {:?}", s, Curve::NAMES))
+    }
+}
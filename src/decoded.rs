@@ -0,0 +1,93 @@
+use ark_ec::{PairingEngine};
+use ark_std::{Zero, UniformRand, start_timer, end_timer};
+
+use rand::{Rng};
+
+use crate::{SemiAvidPr, ColumnCommitmentScheme, FileMatrix};
+
+
+/// How to check a decoded file against the column commitments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodedCheckMode {
+    /// Re-derive every column commitment from the decoded file.
+    Recommit,
+    /// Per segment, compare a commitment to a random linear combination of the decoded columns
+    /// with the same linear combination of the column commitments. A disagreement goes
+    /// undetected with probability at most `1/|Fr|` per segment.
+    RandomLinearCombination,
+}
+
+/// The decoded file disagrees with the column commitments in `segments`, and in particular in
+/// the columns `columns` (pairs of segment and column) if the check identifies them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodingMismatch {
+    pub segments: Vec<usize>,
+    pub columns: Option<Vec<(usize, usize)>>,
+}
+
+impl core::fmt::Display for DecodingMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.columns {
+            Some(columns) => write!(f, "decoded file disagrees with the column commitments of (segment, column) {:?}", columns),
+            None => write!(f, "decoded file disagrees with the column commitments in segments {:?}", self.segments),
+        }
+    }
+}
+
+impl std::error::Error for DecodingMismatch {}
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
+    /// Check the output of `retrieve_decode_rows` against the column commitments, for
+    /// retrievers that want an end-to-end guarantee independent of the per-chunk checks (or
+    /// skipped them).
+    pub fn retrieve_check_decoded<R: Rng + ?Sized>(&self, mut rng: &mut R, column_commitments: &[C::Commitment], data_decoded: &FileMatrix<E::Fr>, mode: DecodedCheckMode) -> Result<(), DecodingMismatch> {
+        assert!(column_commitments.len() == self.get_num_column_commitments());
+        assert!(data_decoded.height() == self.get_num_rows() && data_decoded.width() == self.k);
+
+        let timer_outer = start_timer!(|| "Checking decoded file against column commitments");
+        let mismatch = match mode {
+            DecodedCheckMode::Recommit => {
+                let mut columns = Vec::new();
+                for s in 0..self.num_segments {
+                    for i in 0..self.k {
+                        if self.commit_column(data_decoded, s, i) != column_commitments[s*self.k + i] {
+                            columns.push((s, i));
+                        }
+                    }
+                }
+
+                let mut segments: Vec<usize> = columns.iter().map(|&(s, _)| s).collect();
+                segments.dedup();
+                DecodingMismatch { segments, columns: Some(columns) }
+            },
+            DecodedCheckMode::RandomLinearCombination => {
+                let segments = (0..self.num_segments).filter(|&s| {
+                    let timer_inner = start_timer!(|| "Random linear combination of segment");
+                    let coeffs: Vec<E::Fr> = (0..self.k).map(|_| E::Fr::rand(&mut rng)).collect();
+
+                    let mut combination = vec![E::Fr::zero(); self.L];
+                    for (i, coeff) in coeffs.iter().enumerate() {
+                        for (c, v) in combination.iter_mut().zip(self.column_segment(data_decoded, s, i)) {
+                            *c += *coeff * v;
+                        }
+                    }
+                    let commitment_expected = self.commitment_scheme.combine(&column_commitments[s*self.k..(s+1)*self.k], &coeffs);
+                    let disagrees = self.commitment_scheme.commit(&combination) != commitment_expected;
+
+                    end_timer!(timer_inner);
+                    disagrees
+                }).collect();
+
+                DecodingMismatch { segments, columns: None }
+            },
+        };
+        end_timer!(timer_outer);
+
+        if mismatch.segments.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatch)
+        }
+    }
+}
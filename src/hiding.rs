@@ -0,0 +1,141 @@
+use ark_ec::{PairingEngine};
+use ark_poly::{
+    EvaluationDomain,
+    polynomial::univariate::{DensePolynomial},
+};
+use ark_poly_commit::{
+    PCRandomness,
+    kzg10::{Randomness},
+};
+use ark_std::{start_timer, end_timer};
+
+use rand::{Rng};
+
+use crate::{SemiAvidPr, Kzg, FileMatrix, Opening};
+
+
+/// Blinding polynomial of a hiding column (or coded chunk) commitment.
+pub type Blinding<E> = Randomness<<E as PairingEngine>::Fr, DensePolynomial<<E as PairingEngine>::Fr>>;
+
+
+impl<E: PairingEngine> SemiAvidPr<E> {
+    fn commit_column_hiding(&self, data: &FileMatrix<E::Fr>, segment: usize, idx: usize, blinding: &Blinding<E>) -> E::G1Affine {
+        let poly_poly = self.commitment_scheme.interpolate(self.column_segment(data, segment, idx));
+        self.commitment_scheme.commit_polynomial_hiding(&poly_poly, blinding)
+    }
+
+
+    /// Hiding column commitments, each blinded by a fresh random polynomial of degree
+    /// `hiding_bound + 1`, so that they reveal nothing about the file beyond the entries opened
+    /// as long as no column is opened at more than `hiding_bound` points. Returns the
+    /// blindings alongside, in the same order as the commitments.
+    pub fn disperse_compute_column_commitments_hiding<R: Rng + ?Sized>(&self, rng: &mut R, data_uncoded: &FileMatrix<E::Fr>, hiding_bound: usize) -> (Vec<E::G1Affine>, Vec<Blinding<E>>) {
+        assert!(hiding_bound + 1 < self.L, "Hiding bound must be less than L-1");
+        let mut column_commitments = Vec::new();
+        let mut blindings = Vec::new();
+
+        let timer_outer = start_timer!(|| "Computing hiding column commitments");
+        for s in 0..self.num_segments {
+            for i in 0..self.k {
+                let timer_inner = start_timer!(|| format!("Segment {} column {}", s, i));
+
+                let blinding = Blinding::<E>::rand(hiding_bound, false, None, &mut &mut *rng);
+                column_commitments.push(self.commit_column_hiding(data_uncoded, s, i, &blinding));
+                blindings.push(blinding);
+
+                end_timer!(timer_inner);
+            }
+        }
+        end_timer!(timer_outer);
+
+        (column_commitments, blindings)
+    }
+
+
+    /// Blindings of the coded chunk commitments, per node and segment, i.e., the column
+    /// blindings combined like the column commitments in the homomorphic encoding. The
+    /// client sends node `i` its chunk together with `chunk_blindings[i]`.
+    pub fn disperse_encode_blindings(&self, blindings: &[Blinding<E>]) -> Vec<Vec<Blinding<E>>> {
+        assert!(blindings.len() == self.get_num_column_commitments());
+
+        let timer = start_timer!(|| "'Encoding' of column blindings");
+        let chunk_blindings = (0..self.n).map(|i| {
+            let coeffs = self.encoding_coefficients(i);
+            (0..self.num_segments).map(|s| {
+                blindings[s*self.k..(s+1)*self.k].iter().zip(coeffs.iter())
+                    .fold(Blinding::<E>::empty(), |acc, (r, x)| acc + (*x, r))
+            }).collect()
+        }).collect();
+        end_timer!(timer);
+
+        chunk_blindings
+    }
+
+
+    /// Like `disperse_verify_chunks`, against hiding column commitments, with the blindings
+    /// `chunk_blindings` (per node and segment) of `disperse_encode_blindings`.
+    pub fn disperse_verify_chunks_hiding(&self, column_commitments: &[E::G1Affine], data_coded: &FileMatrix<E::Fr>, chunk_blindings: &[Vec<Blinding<E>>]) -> bool {
+        assert!(chunk_blindings.len() == self.n);
+
+        let timer_outer = start_timer!(|| "Checking coded columns against hiding commitments");
+        for (i, chunk_blindings) in chunk_blindings.iter().enumerate() {
+            for (s, blinding) in chunk_blindings.iter().enumerate() {
+                let timer_inner = start_timer!(|| format!("Column {} segment {}", i, s));
+
+                let commitment = self.commit_column_hiding(data_coded, s, i, blinding);
+                let commitment_check = self.encode_commitments(column_commitments, s, i);
+                if commitment != commitment_check {
+                    return false;
+                }
+
+                end_timer!(timer_inner);
+            }
+        }
+        end_timer!(timer_outer);
+
+        true
+    }
+
+
+    /// Like `retrieve_verify_chunks`, against hiding column commitments, with the blindings
+    /// `chunk_blindings_downloaded` (per downloaded chunk and segment) that the nodes return
+    /// along with their chunks.
+    pub fn retrieve_verify_chunks_hiding(&self, column_commitments: &[E::G1Affine], data_coded_downloaded: &FileMatrix<E::Fr>, chunk_blindings_downloaded: &[Vec<Blinding<E>>], idxs_download_nodes: &[usize]) -> bool {
+        assert!(chunk_blindings_downloaded.len() == idxs_download_nodes.len());
+        let timer_all = start_timer!(|| "Verifying downloaded chunks against hiding commitments");
+
+        let coded_chunk_commitments_affine = self.encode_commitments_all(column_commitments);
+
+        let timer_outer = start_timer!(|| "Checking downloaded coded columns");
+        for (idx, col) in idxs_download_nodes.iter().enumerate() {
+            for (s, coded_chunk_commitments_affine) in coded_chunk_commitments_affine.iter().enumerate() {
+                let timer_inner = start_timer!(|| format!("Column {} segment {}", idx, s));
+
+                let commitment = self.commit_column_hiding(data_coded_downloaded, s, idx, &chunk_blindings_downloaded[idx][s]);
+                if commitment != coded_chunk_commitments_affine[*col] {
+                    return false;
+                }
+
+                end_timer!(timer_inner);
+            }
+        }
+        end_timer!(timer_outer);
+
+        end_timer!(timer_all);
+        true
+    }
+
+
+    /// Like `sampling_open_entry`, against hiding column commitments. The proof carries the
+    /// evaluation of the blinding polynomial, so `sampling_verify_entry` checks it as is.
+    pub fn sampling_open_entry_hiding(&self, column_commitments: &[E::G1Affine], data_uncoded: &FileMatrix<E::Fr>, blindings: &[Blinding<E>], row: usize, col: usize) -> Opening<E::Fr, Kzg<E>> {
+        let (segment, row_in_segment) = (row / self.L, row % self.L);
+
+        let timer = start_timer!(|| "Hiding opening proof");
+        let poly_poly = self.commitment_scheme.interpolate(self.column_segment(data_uncoded, segment, col));
+        let proof = self.commitment_scheme.open_polynomial_hiding(&poly_poly, &blindings[segment*self.k + col], self.domain_polycommit.element(row_in_segment));
+        end_timer!(timer);
+
+        (data_uncoded.get(row, col), row, col, column_commitments.to_vec(), proof)
+    }
+}
@@ -0,0 +1,210 @@
+use ark_ec::{ProjectiveCurve, AffineCurve, PairingEngine, msm::{VariableBaseMSM}};
+use ark_ff::fields::{PrimeField};
+use ark_poly::{
+    Polynomial, UVPolynomial,
+    EvaluationDomain, GeneralEvaluationDomain,
+    polynomial::univariate::{DensePolynomial},
+    evaluations::univariate::{Evaluations},
+};
+use ark_poly_commit::{
+    PCRandomness,
+    kzg10::{KZG10, Powers, VerifierKey, Commitment, Proof, Randomness},
+};
+use ark_serialize::{CanonicalSerialize};
+use ark_std::{Zero, One, start_timer, end_timer};
+
+use rand::{Rng};
+
+use crate::{ColumnCommitmentScheme};
+
+
+/// KZG commitments to columns, with the SRS in monomial and in Lagrange basis.
+pub struct Kzg<E: PairingEngine> {
+    pub(crate) domain: GeneralEvaluationDomain<E::Fr>,
+
+    pub(crate) ck: Powers<'static, E>,
+    pub(crate) ck_lagrange: Vec<E::G1Affine>,
+    pub(crate) vk: VerifierKey<E>,
+    /// Powers of h in G2 with negative exponents, to check multi-point openings. Empty unless
+    /// set up with `setup_with_neg_powers_of_h`.
+    pub(crate) neg_powers_of_h: Vec<E::G2Affine>,
+}
+
+
+impl<E: PairingEngine> Kzg<E> {
+    /// Like `ColumnCommitmentScheme::setup`, and if `with_neg_powers_of_h`, also generate the
+    /// `L` powers of h in G2 that partial retrieval needs (at considerable extra cost).
+    pub(crate) fn setup_with_neg_powers_of_h<R: Rng + ?Sized>(mut rng: &mut R, domain: GeneralEvaluationDomain<E::Fr>, with_neg_powers_of_h: bool) -> Self {
+        #[allow(non_snake_case)]
+        let L = domain.size();
+
+        let timer = start_timer!(|| "KZG setup and preprocessing of setup");
+        let kzg10_pp = KZG10::<E, DensePolynomial<E::Fr>>::setup(L-1, with_neg_powers_of_h, &mut rng).unwrap();
+
+        // https://github.com/arkworks-rs/poly-commit/blob/4d78d534cb55a9b13f34dd76b9702cae3ab2a2a1/src/kzg10/mod.rs#L459
+        let (ck, vk) = {
+            let powers_of_g = kzg10_pp.powers_of_g[..=(L-1)].to_vec();
+            let powers_of_gamma_g = (0..=(L-1))
+                .map(|i| kzg10_pp.powers_of_gamma_g[&i])
+                .collect();
+
+            let powers = Powers {
+                powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g),
+                powers_of_gamma_g: ark_std::borrow::Cow::Owned(powers_of_gamma_g),
+            };
+            let vk = VerifierKey::<E> {
+                g: kzg10_pp.powers_of_g[0],
+                gamma_g: kzg10_pp.powers_of_gamma_g[&0],
+                h: kzg10_pp.h,
+                beta_h: kzg10_pp.beta_h,
+                prepared_h: kzg10_pp.prepared_h.clone(),
+                prepared_beta_h: kzg10_pp.prepared_beta_h.clone(),
+            };
+
+            (powers, vk)
+        };
+        let neg_powers_of_h: Vec<E::G2Affine> = if with_neg_powers_of_h {
+            (0..=(L-1)).map(|i| kzg10_pp.neg_powers_of_h[&i]).collect()
+        } else {
+            Vec::new()
+        };
+        end_timer!(timer);
+
+        let timer = start_timer!(|| "Lagrange-basis SRS");
+        let ck_lagrange = {
+            let mut lagrange_g: Vec<E::G1Projective> = ck.powers_of_g.iter().map(|g: &E::G1Affine| g.into_projective()).collect();
+            domain.ifft_in_place(&mut lagrange_g);
+            E::G1Projective::batch_normalization_into_affine(&lagrange_g)
+        };
+        end_timer!(timer);
+
+        Kzg { domain, ck, ck_lagrange, vk, neg_powers_of_h }
+    }
+
+    pub(crate) fn unwrap_commitment(c: (Commitment<E>, Randomness<E::Fr, DensePolynomial<E::Fr>>)) -> E::G1Affine {
+        c.0.0
+    }
+
+    pub(crate) fn wrap_commitment(c: E::G1Affine) -> (Commitment<E>, Randomness<E::Fr, DensePolynomial<E::Fr>>) {
+        (Commitment::<E>(c), Randomness::<E::Fr, DensePolynomial<E::Fr>>::empty())
+    }
+
+
+    pub(crate) fn interpolate(&self, evals: &[E::Fr]) -> DensePolynomial<E::Fr> {
+        Evaluations::from_vec_and_domain(evals.to_vec(), self.domain).interpolate()
+    }
+
+    pub(crate) fn commit_polynomial(&self, poly_poly: &DensePolynomial<E::Fr>) -> E::G1Affine {
+        Self::commit_polynomial_with(&self.ck, poly_poly)
+    }
+
+    pub(crate) fn open_polynomial(&self, poly_poly: &DensePolynomial<E::Fr>, point: E::Fr) -> Proof<E> {
+        Self::open_polynomial_with(&self.ck, poly_poly, point)
+    }
+
+    /// Like `commit_polynomial`, with a commitment key held elsewhere (see `ClientParams`).
+    pub(crate) fn commit_polynomial_with(ck: &Powers<E>, poly_poly: &DensePolynomial<E::Fr>) -> E::G1Affine {
+        Self::unwrap_commitment(KZG10::commit(ck, poly_poly, None, None).unwrap())
+    }
+
+    pub(crate) fn open_polynomial_with(ck: &Powers<E>, poly_poly: &DensePolynomial<E::Fr>, point: E::Fr) -> Proof<E> {
+        // let proof = KZG10::open(&self.ck, &poly_poly, point, None).unwrap();
+        // Unfortunately, KZG10::open() is pub(crate) only, so inline ... >>>
+        assert!(poly_poly.degree() + 1 <= ck.size());
+        let divisor = DensePolynomial::<E::Fr>::from_coefficients_vec(vec![-point, E::Fr::one()]);
+        let witness_polynomial = poly_poly / &divisor;
+        assert!(witness_polynomial.degree() + 1 <= ck.size());
+        Proof { w: Self::commit_polynomial_with(ck, &witness_polynomial), random_v: None }
+        // <<< ... end of inline!
+    }
+
+    /// Commitment to the column with entries `evals`, as one MSM with the Lagrange-basis SRS.
+    pub(crate) fn commit_lagrange(ck_lagrange: &[E::G1Affine], evals: &[E::Fr]) -> E::G1Affine {
+        assert!(evals.len() == ck_lagrange.len());
+        let scalars: Vec<_> = evals.iter().map(|v| v.into_repr()).collect();
+        VariableBaseMSM::multi_scalar_mul(ck_lagrange, &scalars).into_affine()
+    }
+
+
+    /// Hiding commitment to `poly_poly`, blinded by `blinding` in the powers of `gamma_g`.
+    pub(crate) fn commit_polynomial_hiding(&self, poly_poly: &DensePolynomial<E::Fr>, blinding: &Randomness<E::Fr, DensePolynomial<E::Fr>>) -> E::G1Affine {
+        assert!(blinding.blinding_polynomial.degree() < self.ck.powers_of_gamma_g.len());
+        let blinding_coeffs: Vec<_> = blinding.blinding_polynomial.coeffs().iter().map(|c| c.into_repr()).collect();
+        let commitment_blinding = VariableBaseMSM::multi_scalar_mul(&self.ck.powers_of_gamma_g, &blinding_coeffs);
+        (commitment_blinding + self.commit_polynomial(poly_poly).into_projective()).into_affine()
+    }
+
+    /// Opening of a hiding commitment, with the evaluation of the blinding polynomial at
+    /// `point` in `random_v`, so that `KZG10::check` accounts for it with `gamma_g` of `vk`.
+    pub(crate) fn open_polynomial_hiding(&self, poly_poly: &DensePolynomial<E::Fr>, blinding: &Randomness<E::Fr, DensePolynomial<E::Fr>>, point: E::Fr) -> Proof<E> {
+        let (witness_polynomial, blinding_witness_polynomial) = KZG10::<E, DensePolynomial<E::Fr>>::compute_witness_polynomial(poly_poly, point, blinding).unwrap();
+        let mut witness_blinding = Randomness::empty();
+        if let Some(blinding_witness_polynomial) = blinding_witness_polynomial {
+            witness_blinding.blinding_polynomial = blinding_witness_polynomial;
+        }
+        Proof {
+            w: self.commit_polynomial_hiding(&witness_polynomial, &witness_blinding),
+            random_v: Some(blinding.blinding_polynomial.evaluate(&point)),
+        }
+    }
+}
+
+
+impl<E: PairingEngine> ColumnCommitmentScheme<E::Fr> for Kzg<E> {
+    type Commitment = E::G1Affine;
+    type Proof = Proof<E>;
+
+
+    fn setup<R: Rng + ?Sized>(rng: &mut R, domain: GeneralEvaluationDomain<E::Fr>) -> Self {
+        Self::setup_with_neg_powers_of_h(rng, domain, false)
+    }
+
+    fn commit(&self, evals: &[E::Fr]) -> E::G1Affine {
+        let timer = start_timer!(|| "Poly evaluations and interpolation");
+        let poly_poly = self.interpolate(evals);
+        end_timer!(timer);
+
+        let timer = start_timer!(|| "KZG commitment");
+        let commitment = self.commit_polynomial(&poly_poly);
+        end_timer!(timer);
+
+        commitment
+    }
+
+    fn combine(&self, commitments: &[E::G1Affine], coeffs: &[E::Fr]) -> E::G1Affine {
+        assert!(commitments.len() == coeffs.len());
+        commitments.iter().zip(coeffs.iter())
+            .fold(E::G1Projective::zero(), |acc, (c, x)| acc + c.mul(x.into_repr()))
+            .into_affine()
+    }
+
+    fn encode(&self, commitments: &[E::G1Affine], domain: GeneralEvaluationDomain<E::Fr>) -> Vec<E::G1Affine> {
+        // the coded chunk commitments are the FFT of the column commitments
+        let mut commitments_projective: Vec<E::G1Projective> = commitments.iter().map(|h| h.into_projective()).collect();
+        commitments_projective.resize(domain.size(), E::G1Projective::zero());
+        domain.fft_in_place(&mut commitments_projective);
+        E::G1Projective::batch_normalization_into_affine(&commitments_projective)
+    }
+
+    fn open(&self, evals: &[E::Fr], point: E::Fr) -> (E::Fr, Proof<E>) {
+        let poly_poly = self.interpolate(evals);
+        (poly_poly.evaluate(&point), self.open_polynomial(&poly_poly, point))
+    }
+
+    fn verify(&self, commitment: &E::G1Affine, point: E::Fr, value: E::Fr, proof: &Proof<E>) -> bool {
+        let commitment = Self::wrap_commitment(*commitment).0;
+        KZG10::<E, DensePolynomial<E::Fr>>::check(&self.vk, &commitment, point, value, proof).unwrap()
+    }
+
+    fn proof_size(proof: &Proof<E>) -> usize {
+        proof.serialized_size()
+    }
+
+    fn batch_verify<R: Rng + ?Sized>(&self, rng: &mut R, commitments: &[E::G1Affine], points: &[E::Fr], values: &[E::Fr], proofs: &[Proof<E>]) -> bool {
+        if commitments.len() != points.len() || points.len() != values.len() || values.len() != proofs.len() {
+            return false;
+        }
+        let commitments: Vec<Commitment<E>> = commitments.iter().map(|c| Self::wrap_commitment(*c).0).collect();
+        KZG10::<E, DensePolynomial<E::Fr>>::batch_check(&self.vk, &commitments, points, values, proofs, &mut &mut *rng).unwrap()
+    }
+}
@@ -1,44 +1,141 @@
 use ark_ff::fields::{Field, PrimeField, FpParameters};
-use ark_ec::{ProjectiveCurve, AffineCurve, PairingEngine};
+use ark_ec::{PairingEngine};
 use ark_poly::{
-    Polynomial, UVPolynomial,
+    UVPolynomial,
     EvaluationDomain, GeneralEvaluationDomain,
     polynomial::univariate::{DensePolynomial},
-    evaluations::univariate::{Evaluations},
 };
-use ark_poly_commit::{
-    PCRandomness,
-    kzg10::{KZG10, Powers, VerifierKey, Commitment, Proof, Randomness},
-};
-use ark_std::{Zero, One, UniformRand, start_timer, end_timer};
+use ark_std::{UniformRand, start_timer, end_timer};
 
 use rand::{Rng};
 
+use std::sync::{Arc, Mutex};
+
+
+mod commitment;
+pub use crate::commitment::{ColumnCommitmentScheme};
+
+mod kzg;
+pub use crate::kzg::{Kzg};
+
+mod pedersen;
+pub use crate::pedersen::{Pedersen, IpaProof};
 
 mod utils;
-use crate::utils::{Matrix};
+pub use crate::utils::{Matrix, FileMatrix, LuDecomposition, SingularMatrix, vandermonde_inverse};
+
+mod streaming;
+pub use crate::streaming::{DispersalStream};
+
+mod update;
+pub use crate::update::{EntryUpdate};
+
+pub mod outofcore;
+
+mod cache;
+pub use crate::cache::{DecoderCache};
+
+mod partial;
+pub use crate::partial::{RowsOpening};
+
+mod redundant;
+pub use crate::redundant::{RedundantRetrievalMode, RedundantRetrievalReport, InconsistentChunks};
+
+mod repair;
+
+mod lrc;
+pub use crate::lrc::{LrcLayout};
+
+mod audit;
+pub use crate::audit::{AuditChallenge, AuditResponse, AuditScheduler, AuditStats, InvalidChallenge};
+
+mod decoded;
+pub use crate::decoded::{DecodedCheckMode, DecodingMismatch};
+
+mod hiding;
+pub use crate::hiding::{Blinding};
+
+mod confidential;
+pub use crate::confidential::{ConfidentialLayout};
+
+mod curves;
+pub use crate::curves::{Curve, CurveVisitor, NamedCurve};
+
+mod params;
+pub use crate::params::{ClientParams, NodeParams, SamplerParams};
+
+mod simulation;
+pub use crate::simulation::{Actor, ClientBehavior, Fault, LinkConfig, NodeBehavior, SimConfig, SimReport};
+
+mod certificate;
+pub use crate::certificate::{ChunkId, DispersalCertificate, FileId, NodeRegistry, PublicKey, Signature, SigningKey};
 
 #[cfg(test)]
 mod tests;
 
 
+/// Opening of an entry of the file by `sampling_open_entry`: its value, row and column, the
+/// column commitments, and the proof.
+pub type Opening<F, C> = (F, usize, usize, Vec<<C as ColumnCommitmentScheme<F>>::Commitment>, <C as ColumnCommitmentScheme<F>>::Proof);
+
+
+/// Number of decoding matrices that `SemiAvidPr` keeps by default.
+pub const DECODER_CACHE_CAPACITY_DEFAULT: usize = 16;
+
+
+/// Semi-AVID-PR over the scalar field of `E`, with columns committed to by `C`.
+///
+/// The scheme owns its parameters and shares them, like the decoder cache, between its clones,
+/// so a clone is a cheap handle that can be passed to other threads.
 #[allow(non_snake_case)]
-pub struct SemiAvidPr<'a, E: PairingEngine> {
+pub struct SemiAvidPr<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr> = Kzg<E>> {
     n: usize,
     k: usize,
     L: usize,
+    num_segments: usize,
 
     domain_polycommit: GeneralEvaluationDomain<E::Fr>,
     domain_encoding: GeneralEvaluationDomain<E::Fr>,
 
-    kzg10_ck: Powers<'a, E>,
-    kzg10_vk: VerifierKey<E>,
+    commitment_scheme: Arc<C>,
+
+    decoder_cache: Arc<Mutex<DecoderCache<E::Fr>>>,
 }
 
 
-impl<E: PairingEngine> SemiAvidPr<'_, E> {
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> Clone for SemiAvidPr<E, C> {
+    fn clone(&self) -> Self {
+        Self {
+            n: self.n, k: self.k, L: self.L, num_segments: self.num_segments,
+
+            domain_polycommit: self.domain_polycommit,
+            domain_encoding: self.domain_encoding,
+
+            commitment_scheme: Arc::clone(&self.commitment_scheme),
+
+            decoder_cache: Arc::clone(&self.decoder_cache),
+        }
+    }
+}
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
     #[allow(non_snake_case)]
-    pub fn setup<R: Rng + ?Sized>(mut rng: &mut R, n: usize, k: usize, L: usize) -> Self {
+    pub fn setup<R: Rng + ?Sized>(rng: &mut R, n: usize, k: usize, L: usize) -> Self {
+        Self::setup_with_segments(rng, n, k, L, 1)
+    }
+
+    /// Setup for files whose columns span `num_segments` polynomials of `L` entries each,
+    /// so that files of `k * L * num_segments` elements can be dispersed with an SRS of size `L`.
+    #[allow(non_snake_case)]
+    pub fn setup_with_segments<R: Rng + ?Sized>(rng: &mut R, n: usize, k: usize, L: usize, num_segments: usize) -> Self {
+        Self::setup_with(n, k, L, num_segments, |domain_polycommit| C::setup(rng, domain_polycommit))
+    }
+
+    /// Setup with the commitment scheme that `setup_commitment_scheme` creates for the domain.
+    #[allow(non_snake_case)]
+    pub(crate) fn setup_with<F: FnOnce(GeneralEvaluationDomain<E::Fr>) -> C>(n: usize, k: usize, L: usize, num_segments: usize, setup_commitment_scheme: F) -> Self {
+        assert!(num_segments >= 1);
         assert!(n.is_power_of_two());
         assert!(L.is_power_of_two());
 
@@ -47,59 +144,43 @@ impl<E: PairingEngine> SemiAvidPr<'_, E> {
         let domain_encoding: GeneralEvaluationDomain<E::Fr> = ark_poly::domain::EvaluationDomain::<E::Fr>::new(n).unwrap();
         end_timer!(timer);
 
-        let timer = start_timer!(|| "KZG setup and preprocessing of setup");
-        let kzg10_pp = KZG10::<E, DensePolynomial<E::Fr>>::setup(L-1, false, &mut rng).unwrap();
-
-        // https://github.com/arkworks-rs/poly-commit/blob/4d78d534cb55a9b13f34dd76b9702cae3ab2a2a1/src/kzg10/mod.rs#L459
-        let (kzg10_ck, kzg10_vk) = {
-            let powers_of_g = kzg10_pp.powers_of_g[..=(L-1)].to_vec();
-            let powers_of_gamma_g = (0..=(L-1))
-                .map(|i| kzg10_pp.powers_of_gamma_g[&i])
-                .collect();
-
-            let powers = Powers {
-                powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g),
-                powers_of_gamma_g: ark_std::borrow::Cow::Owned(powers_of_gamma_g),
-            };
-            let vk = VerifierKey::<E> {
-                g: kzg10_pp.powers_of_g[0],
-                gamma_g: kzg10_pp.powers_of_gamma_g[&0],
-                h: kzg10_pp.h,
-                beta_h: kzg10_pp.beta_h,
-                prepared_h: kzg10_pp.prepared_h.clone(),
-                prepared_beta_h: kzg10_pp.prepared_beta_h.clone(),
-            };
-
-            (powers, vk)
-        };
-        end_timer!(timer);
+        let commitment_scheme = setup_commitment_scheme(domain_polycommit);
 
         Self {
-            n, k, L,
+            n, k, L, num_segments,
 
             domain_polycommit,
             domain_encoding,
 
-            kzg10_ck,
-            kzg10_vk,
+            commitment_scheme: Arc::new(commitment_scheme),
+
+            decoder_cache: Arc::new(Mutex::new(DecoderCache::new(DECODER_CACHE_CAPACITY_DEFAULT))),
         }
     }
 
 
     pub fn get_filesize(&self) -> usize {
-        (<E::Fr as PrimeField>::Params::CAPACITY as usize) * self.k * self.L
+        (<E::Fr as PrimeField>::Params::CAPACITY as usize) * self.k * self.L * self.num_segments
     }
 
     pub fn get_filesize_in_bytes(&self) -> u64 {
         (self.get_filesize() / 8) as u64
     }
 
+    pub fn get_num_segments(&self) -> usize {
+        self.num_segments
+    }
+
+    pub fn get_num_rows(&self) -> usize {
+        self.L * self.num_segments
+    }
+
     pub fn get_num_column_commitments(&self) -> usize {
-        return self.k
+        return self.k * self.num_segments
     }
 
     pub fn get_num_row_encodings(&self) -> usize {
-        return self.L
+        return self.get_num_rows()
     }
 
     pub fn get_num_chunk_verifications(&self) -> usize {
@@ -111,17 +192,28 @@ impl<E: PairingEngine> SemiAvidPr<'_, E> {
     }
 
     pub fn get_num_row_decodings(&self) -> usize {
-        return self.L
+        return self.get_num_rows()
     }
 
+    /// Number of hits and misses of the decoding matrix cache so far.
+    pub fn get_decoder_cache_stats(&self) -> (usize, usize) {
+        let cache = self.decoder_cache.lock().unwrap();
+        (cache.get_num_hits(), cache.get_num_misses())
+    }
+
+    /// Bound the number of cached decoding matrices, where 0 disables caching.
+    pub fn set_decoder_cache_capacity(&self, capacity: usize) {
+        self.decoder_cache.lock().unwrap().set_capacity(capacity);
+    }
 
-    pub fn generate_random_file<R: Rng + ?Sized>(&self, mut rng: &mut R) -> Vec<Vec<E::Fr>> {
-        let mut data = vec![vec![E::Fr::zero(); self.k]; self.L];
+
+    pub fn generate_random_file<R: Rng + ?Sized>(&self, mut rng: &mut R) -> FileMatrix<E::Fr> {
+        let mut data = FileMatrix::zeros(self.get_num_rows(), self.k);
 
         let timer = start_timer!(|| "Sampling random field elements");
         for i in 0..self.k {
-            for j in 0..self.L {
-                data[j][i] = E::Fr::rand(&mut rng);
+            for v in data.column_mut(i) {
+                *v = E::Fr::rand(&mut rng);
             }
         }
         end_timer!(timer);
@@ -130,55 +222,64 @@ impl<E: PairingEngine> SemiAvidPr<'_, E> {
     }
 
 
-    fn unwrap_commitment(c: (Commitment<E>, Randomness<E::Fr, DensePolynomial<E::Fr>>)) -> E::G1Affine {
-        c.0.0
+    /// The commitment scheme for the columns, e.g., for its proof sizes or to check openings.
+    pub fn get_commitment_scheme(&self) -> &C {
+        &self.commitment_scheme
     }
 
-    fn wrap_commitment(c: E::G1Affine) -> (Commitment<E>, Randomness<E::Fr, DensePolynomial<E::Fr>>) {
-        (Commitment::<E>(c), Randomness::<E::Fr, DensePolynomial<E::Fr>>::empty())
+
+    fn column_segment<'d>(&self, data: &'d FileMatrix<E::Fr>, segment: usize, idx: usize) -> &'d [E::Fr] {
+        &data.column(idx)[segment*self.L..(segment+1)*self.L]
     }
 
 
-    fn commit_column(&self, data: &Vec<Vec<E::Fr>>, idx: usize) -> E::G1Affine {
-        let timer = start_timer!(|| "Poly evaluations and interpolation");
-        let poly_evals = Evaluations::from_vec_and_domain(data.iter().map(|r| r[idx]).collect(), self.domain_polycommit);
-        let poly_poly = poly_evals.interpolate();
-        end_timer!(timer);
-        
-        let timer = start_timer!(|| "KZG commitment");
-        let commitment = Self::unwrap_commitment(KZG10::commit(&self.kzg10_ck, &poly_poly, None, None).unwrap());
+    fn commit_column(&self, data: &FileMatrix<E::Fr>, segment: usize, idx: usize) -> C::Commitment {
+        self.commitment_scheme.commit(self.column_segment(data, segment, idx))
+    }
+
+
+    /// Coefficients with which the column commitments of a segment combine to the commitment
+    /// to the coded chunk of node `idx`, i.e., the encoding vector of `idx`.
+    fn encoding_coefficients(&self, idx: usize) -> Vec<E::Fr> {
+        let point = self.domain_encoding.element(idx);
+        (0..self.k as u64).map(|j| point.pow([j])).collect()
+    }
+
+
+    fn encode_commitments(&self, column_commitments: &[C::Commitment], segment: usize, idx: usize) -> C::Commitment {
+        let timer = start_timer!(|| "'Encoding' of column commitments");
+        let commitment = self.commitment_scheme.combine(&column_commitments[segment*self.k..(segment+1)*self.k], &self.encoding_coefficients(idx));
         end_timer!(timer);
 
         commitment
     }
 
 
-    fn encode_commitments(&self, column_commitments: &Vec<E::G1Affine>, idx: usize) -> E::G1Affine {
-        let timer = start_timer!(|| "'Encoding' of KZG column commitments");
-        let mut commitment = E::G1Projective::zero();
-        for j in 0..self.k {
-            let j_in_field = E::Fr::from_le_bytes_mod_order(&j.to_le_bytes());
-            let eval_exponent = self.domain_encoding.element(idx).pow(j_in_field.into_repr());
-            commitment += column_commitments[j].mul(eval_exponent);
-        }
-        let commitment = commitment.into_affine();
+    /// Commitments to all coded chunks, for every segment, from the column commitments.
+    fn encode_commitments_all(&self, column_commitments: &[C::Commitment]) -> Vec<Vec<C::Commitment>> {
+        let timer = start_timer!(|| "'Encoding' of column commitments to coded chunk commitments");
+        let coded_chunk_commitments = (0..self.num_segments).map(|s| {
+            self.commitment_scheme.encode(&column_commitments[s*self.k..(s+1)*self.k], self.domain_encoding)
+        }).collect();
         end_timer!(timer);
 
-        commitment
+        coded_chunk_commitments
     }
 
 
-    pub fn disperse_compute_column_commitments(&self, data_uncoded: &Vec<Vec<E::Fr>>) -> Vec<E::G1Affine> {
+    pub fn disperse_compute_column_commitments(&self, data_uncoded: &FileMatrix<E::Fr>) -> Vec<C::Commitment> {
         let mut column_commitments = Vec::new();
 
         let timer_outer = start_timer!(|| "Computing column commitments");
-        for i in 0..self.k {
-            let timer_inner = start_timer!(|| format!("Column {}", i));
+        for s in 0..self.num_segments {
+            for i in 0..self.k {
+                let timer_inner = start_timer!(|| format!("Segment {} column {}", s, i));
 
-            let commitment = self.commit_column(&data_uncoded, i);
-            column_commitments.push(commitment);
+                let commitment = self.commit_column(data_uncoded, s, i);
+                column_commitments.push(commitment);
 
-            end_timer!(timer_inner);
+                end_timer!(timer_inner);
+            }
         }
         end_timer!(timer_outer);
 
@@ -186,37 +287,30 @@ impl<E: PairingEngine> SemiAvidPr<'_, E> {
     }
 
 
-    pub fn disperse_encode_rows(&self, data_uncoded: &Vec<Vec<E::Fr>>) -> Vec<Vec<E::Fr>> {
-        let mut data_coded = Vec::new();
-
-        let timer_outer = start_timer!(|| "Encoding rows");
-        for j in 0..self.L {
-            let timer_inner = start_timer!(|| format!("Row {}", j));
-
-            let poly_poly = DensePolynomial::<E::Fr>::from_coefficients_slice(&data_uncoded[j]);
-            let poly_evals = poly_poly.evaluate_over_domain(self.domain_encoding);
-            data_coded.push(poly_evals.evals);
+    fn encode_row(&self, row: &[E::Fr]) -> Vec<E::Fr> {
+        encode_row(self.domain_encoding, row)
+    }
 
-            end_timer!(timer_inner);
-        }
-        end_timer!(timer_outer);
 
-        data_coded
+    pub fn disperse_encode_rows(&self, data_uncoded: &FileMatrix<E::Fr>) -> FileMatrix<E::Fr> {
+        encode_rows(self.domain_encoding, data_uncoded)
     }
 
 
-    pub fn disperse_verify_chunks(&self, column_commitments: &Vec<E::G1Affine>, data_coded: &Vec<Vec<E::Fr>>) -> bool {
+    pub fn disperse_verify_chunks(&self, column_commitments: &Vec<C::Commitment>, data_coded: &FileMatrix<E::Fr>) -> bool {
         let timer_outer = start_timer!(|| "Checking coded columns");
         for i in 0..self.n {
-            let timer_inner = start_timer!(|| format!("Column {}", i));
+            for s in 0..self.num_segments {
+                let timer_inner = start_timer!(|| format!("Column {} segment {}", i, s));
 
-            let commitment = self.commit_column(&data_coded, i);
-            let commitment_check = self.encode_commitments(&column_commitments, i);
-            if commitment != commitment_check {
-                return false;
-            }
+                let commitment = self.commit_column(data_coded, s, i);
+                let commitment_check = self.encode_commitments(column_commitments, s, i);
+                if commitment != commitment_check {
+                    return false;
+                }
 
-            end_timer!(timer_inner);
+                end_timer!(timer_inner);
+            }
         }
         end_timer!(timer_outer);
 
@@ -224,41 +318,54 @@ impl<E: PairingEngine> SemiAvidPr<'_, E> {
     }
 
 
-    pub fn retrieve_download_chunks(&self, data_coded: &Vec<Vec<E::Fr>>, idxs_download_nodes: &Vec<usize>) -> Vec<Vec<E::Fr>> {
+    /// Check only the chunk of node `idx`, as the node itself does on receiving it.
+    pub fn disperse_verify_chunk(&self, column_commitments: &[C::Commitment], chunk: &[E::Fr], idx: usize) -> bool {
+        if column_commitments.len() != self.get_num_column_commitments() || chunk.len() != self.get_num_rows() || idx >= self.n {
+            return false;
+        }
+
+        let timer = start_timer!(|| format!("Checking coded column {}", idx));
+        let ret_val = (0..self.num_segments).all(|s| {
+            self.commitment_scheme.commit(&chunk[s*self.L..(s+1)*self.L]) == self.encode_commitments(column_commitments, s, idx)
+        });
+        end_timer!(timer);
+
+        ret_val
+    }
+
+
+    pub fn retrieve_download_chunks(&self, data_coded: &FileMatrix<E::Fr>, idxs_download_nodes: &Vec<usize>) -> FileMatrix<E::Fr> {
         let timer = start_timer!(|| "Downloading chunks");
-        let data_coded_downloaded = data_coded.iter().map(|r| idxs_download_nodes.iter().map(|&i| r[i]).collect()).collect();
+        let data_coded_downloaded = data_coded.pick_columns(idxs_download_nodes);
         end_timer!(timer);
 
         data_coded_downloaded
     }
 
 
-    pub fn retrieve_verify_chunks(&self, column_commitments: &Vec<E::G1Affine>, data_coded_downloaded: &Vec<Vec<E::Fr>>, idxs_download_nodes: &Vec<usize>) -> bool {
+    pub fn retrieve_verify_chunks(&self, column_commitments: &Vec<C::Commitment>, data_coded_downloaded: &FileMatrix<E::Fr>, idxs_download_nodes: &Vec<usize>) -> bool {
         let timer_all = start_timer!(|| "Verifying downloaded chunks");
 
-        let timer_encode_commitments = start_timer!(|| "'Encoding' of column commitments to coded chunk commitments");
-        let mut column_commitments_projective: Vec<E::G1Projective> = column_commitments.iter().map(|h| h.clone().into()).collect();
-        column_commitments_projective.resize(self.domain_encoding.size(), E::G1Projective::zero());
-        self.domain_encoding.fft_in_place(&mut column_commitments_projective);
-        let coded_chunk_commitments_affine: Vec<E::G1Affine> = column_commitments_projective.iter().map(|h| h.into_affine()).collect();
-        end_timer!(timer_encode_commitments);
+        let coded_chunk_commitments_affine = self.encode_commitments_all(column_commitments);
 
         let timer_outer = start_timer!(|| "Checking downloaded coded columns");
         for (idx, col) in idxs_download_nodes.iter().enumerate() {
-            let timer_inner = start_timer!(|| format!("Column {}", idx));
+            for (s, coded_chunk_commitments_affine) in coded_chunk_commitments_affine.iter().enumerate() {
+                let timer_inner = start_timer!(|| format!("Column {} segment {}", idx, s));
 
-            let commitment = self.commit_column(&data_coded_downloaded, idx);
+                let commitment = self.commit_column(data_coded_downloaded, s, idx);
 
-            // let commitment_check = self.encode_commitments(&column_commitments, *col);
-            // if commitment != commitment_check {
-            //     return false;
-            // }
+                // let commitment_check = self.encode_commitments(&column_commitments, s, *col);
+                // if commitment != commitment_check {
+                //     return false;
+                // }
 
-            if commitment != coded_chunk_commitments_affine[*col] {
-                return false;
-            }
+                if commitment != coded_chunk_commitments_affine[*col] {
+                    return false;
+                }
 
-            end_timer!(timer_inner);
+                end_timer!(timer_inner);
+            }
         }
         end_timer!(timer_outer);
 
@@ -267,68 +374,111 @@ impl<E: PairingEngine> SemiAvidPr<'_, E> {
     }
     
 
-    pub fn retrieve_prepare_decoding(&self, idxs_download_nodes: &Vec<usize>) -> Matrix<E::Fr> {
+    pub fn retrieve_prepare_decoding(&self, idxs_download_nodes: &[usize]) -> Matrix<E::Fr> {
         assert!(idxs_download_nodes.len() == self.k);
 
-        let mut matrix = Vec::new();
-        for i in 0..self.k {
-            let i_in_field = E::Fr::from_le_bytes_mod_order(&i.to_le_bytes());
-            matrix.push(idxs_download_nodes.iter().map(|&j| self.domain_encoding.element(j).pow(i_in_field.into_repr())).collect());
+        // cache by the sorted indices, and reorder the rows of the decoding matrix to match the
+        // order in which chunks were downloaded
+        let mut order: Vec<usize> = (0..self.k).collect();
+        order.sort_by_key(|&i| idxs_download_nodes[i]);
+        let idxs_sorted: Vec<usize> = order.iter().map(|&i| idxs_download_nodes[i]).collect();
+        let mut positions = vec![0; self.k];
+        for (position, &i) in order.iter().enumerate() {
+            positions[i] = position;
         }
 
-        Matrix::from_nested_vec(self.k, self.k, matrix).invert()
+        let cached = self.decoder_cache.lock().unwrap().get(&idxs_sorted);
+        let decoder_aux_sorted = match cached {
+            Some(decoder_aux_sorted) => decoder_aux_sorted,
+            None => {
+                let timer = start_timer!(|| "Inverting decoding matrix");
+                // the encoding matrix restricted to the downloaded chunks is Vandermonde in these points
+                let points: Vec<E::Fr> = idxs_sorted.iter().map(|&j| self.domain_encoding.element(j)).collect();
+                let decoder_aux_sorted = vandermonde_inverse(&points).expect("Indices of download nodes must be distinct");
+                end_timer!(timer);
+
+                self.decoder_cache.lock().unwrap().insert(idxs_sorted, decoder_aux_sorted.clone());
+                decoder_aux_sorted
+            },
+        };
+
+        decoder_aux_sorted.vpick(&positions)
     }
 
     
-    pub fn retrieve_decode_rows(&self, data_coded_downloaded: &Vec<Vec<E::Fr>>, decoder_aux: &Matrix<E::Fr>) -> Vec<Vec<E::Fr>> {
+    pub fn retrieve_decode_rows(&self, data_coded_downloaded: &FileMatrix<E::Fr>, decoder_aux: &Matrix<E::Fr>) -> FileMatrix<E::Fr> {
         assert!(decoder_aux.height() == decoder_aux.width());
-        let mut data_decoded = Vec::new();
-
-        let timer_outer = start_timer!(|| "Decoding rows");
-        for j in 0..self.L {
-            let timer_inner = start_timer!(|| format!("Row {}", j));
+        assert!(data_coded_downloaded.width() == decoder_aux.height());
 
-            assert!(data_coded_downloaded[j].len() == decoder_aux.height());
-            data_decoded.push((0..decoder_aux.width()).map(|col| (0..decoder_aux.height()).map(|row| decoder_aux.get(row, col) * data_coded_downloaded[j][row]).sum::<E::Fr>()).collect());
-
-            end_timer!(timer_inner);
-        }
-        end_timer!(timer_outer);
+        // all rows at once: (rows of downloaded chunks) x (inverse encoding matrix)
+        let timer = start_timer!(|| "Decoding rows");
+        let data_decoded = data_coded_downloaded.mul(decoder_aux);
+        end_timer!(timer);
 
         data_decoded
     }
 
 
-    pub fn sampling_open_entry(&self, column_commitments: &Vec<E::G1Affine>, data_uncoded: &Vec<Vec<E::Fr>>, row: usize, col: usize) -> (E::Fr, usize, usize, Vec<E::G1Affine>, Proof<E>) {
-        let timer = start_timer!(|| "Poly evaluations and interpolation");
-        let poly_evals = Evaluations::from_vec_and_domain(data_uncoded.iter().map(|r| r[col]).collect(), self.domain_polycommit);
-        let poly_poly = poly_evals.interpolate();
-        end_timer!(timer);
+    pub fn sampling_open_entry(&self, column_commitments: &[C::Commitment], data_uncoded: &FileMatrix<E::Fr>, row: usize, col: usize) -> Opening<E::Fr, C> {
+        let (segment, row_in_segment) = (row / self.L, row % self.L);
 
-        let timer = start_timer!(|| "KZG proof");
-        // let proof = KZG10::open(&self.kzg10_ck, &poly_poly, self.domain_polycommit.element(row), None).unwrap();
-        // Unfortunately, KZG10::open() is pub(crate) only, so inline ... >>>
-        let point = self.domain_polycommit.element(row);
-        assert!(poly_poly.degree() + 1 <= self.kzg10_ck.size());
-        let divisor = DensePolynomial::<E::Fr>::from_coefficients_vec(vec![-point, E::Fr::one()]);
-        let witness_polynomial = &poly_poly / &divisor;
-        assert!(witness_polynomial.degree() + 1 <= self.kzg10_ck.size());
-        let proof = Self::unwrap_commitment(KZG10::commit(&self.kzg10_ck, &witness_polynomial, None, None).unwrap());
-        let proof = Proof { w: proof, random_v: None };
-        // <<< ... end of inline!
+        let timer = start_timer!(|| "Opening proof");
+        let (_, proof) = self.commitment_scheme.open(self.column_segment(data_uncoded, segment, col), self.domain_polycommit.element(row_in_segment));
         end_timer!(timer);
 
-        (data_uncoded[row][col], row, col, column_commitments.clone(), proof)
+        (data_uncoded.get(row, col), row, col, column_commitments.to_vec(), proof)
     }
 
 
-    pub fn sampling_verify_entry(&self, (value, row, col, column_commitments, proof): (E::Fr, usize, usize, Vec<E::G1Affine>, Proof<E>)) -> bool {
-        let timer = start_timer!(|| "KZG check");
-        let commitment = Self::wrap_commitment(column_commitments[col]).0;
-        let point = self.domain_polycommit.element(row);
-        let ret_val = KZG10::<E, DensePolynomial::<E::Fr>>::check(&self.kzg10_vk, &commitment, point, value, &proof).unwrap();
+    pub fn sampling_verify_entry(&self, (value, row, col, column_commitments, proof): Opening<E::Fr, C>) -> bool {
+        let (segment, row_in_segment) = (row / self.L, row % self.L);
+
+        let timer = start_timer!(|| "Opening check");
+        let ret_val = self.commitment_scheme.verify(&column_commitments[segment*self.k + col], self.domain_polycommit.element(row_in_segment), value, &proof);
         end_timer!(timer);
         
         ret_val
     }
+
+
+    /// Check several openings of `sampling_open_entry` at once.
+    pub fn sampling_verify_entries<R: Rng + ?Sized>(&self, rng: &mut R, openings: &[Opening<E::Fr, C>]) -> bool {
+        let timer = start_timer!(|| "Batched opening check");
+        let commitments: Vec<C::Commitment> = openings.iter().map(|(_, row, col, column_commitments, _)| column_commitments[(row / self.L)*self.k + col]).collect();
+        let points: Vec<E::Fr> = openings.iter().map(|(_, row, _, _, _)| self.domain_polycommit.element(row % self.L)).collect();
+        let values: Vec<E::Fr> = openings.iter().map(|(value, _, _, _, _)| *value).collect();
+        let proofs: Vec<C::Proof> = openings.iter().map(|(_, _, _, _, proof)| proof.clone()).collect();
+        let ret_val = self.commitment_scheme.batch_verify(rng, &commitments, &points, &values, &proofs);
+        end_timer!(timer);
+
+        ret_val
+    }
+}
+
+
+/// Reed-Solomon encoding of `row` (coefficients) over `domain_encoding`.
+fn encode_row<F: PrimeField>(domain_encoding: GeneralEvaluationDomain<F>, row: &[F]) -> Vec<F> {
+    let poly_poly = DensePolynomial::<F>::from_coefficients_slice(row);
+    let poly_evals = poly_poly.evaluate_over_domain(domain_encoding);
+    poly_evals.evals
+}
+
+
+/// Encoding of all rows of `data_uncoded`, one coded chunk (column) per point of `domain_encoding`.
+fn encode_rows<F: PrimeField>(domain_encoding: GeneralEvaluationDomain<F>, data_uncoded: &FileMatrix<F>) -> FileMatrix<F> {
+    // work on the transposes, so that rows are contiguous both when reading and when writing
+    let data_uncoded_transposed = data_uncoded.transpose();
+    let mut data_coded_transposed = FileMatrix::zeros(domain_encoding.size(), data_uncoded.height());
+
+    let timer_outer = start_timer!(|| "Encoding rows");
+    for j in 0..data_uncoded.height() {
+        let timer_inner = start_timer!(|| format!("Row {}", j));
+
+        data_coded_transposed.column_mut(j).copy_from_slice(&encode_row(domain_encoding, data_uncoded_transposed.column(j)));
+
+        end_timer!(timer_inner);
+    }
+    end_timer!(timer_outer);
+
+    data_coded_transposed.transpose()
 }
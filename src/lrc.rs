@@ -0,0 +1,127 @@
+use ark_ec::{PairingEngine};
+use ark_std::{One, start_timer, end_timer};
+
+use std::ops::{Range};
+
+use crate::{SemiAvidPr, ColumnCommitmentScheme, FileMatrix};
+
+
+/// Locally repairable layout on top of the Reed-Solomon coded chunks: the `n` storage nodes
+/// are split into consecutive groups of `group_size`, and each group gets an additional local
+/// parity chunk, the sum of the chunks of the group. A lost chunk is then repaired from the
+/// other chunks of its group and the local parity, instead of from `k` chunks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LrcLayout {
+    n: usize,
+    group_size: usize,
+}
+
+impl LrcLayout {
+    pub fn new(n: usize, group_size: usize) -> Self {
+        assert!(group_size >= 1 && n.is_multiple_of(group_size));
+        Self { n, group_size }
+    }
+
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    pub fn num_groups(&self) -> usize {
+        self.n / self.group_size
+    }
+
+    pub fn group_of(&self, idx: usize) -> usize {
+        assert!(idx < self.n);
+        idx / self.group_size
+    }
+
+    pub fn members(&self, group: usize) -> Range<usize> {
+        assert!(group < self.num_groups());
+        group*self.group_size..(group+1)*self.group_size
+    }
+
+    /// Nodes whose chunks, together with the local parity of its group, repair the chunk of `idx`.
+    pub fn repair_sources(&self, idx: usize) -> Vec<usize> {
+        self.members(self.group_of(idx)).filter(|&i| i != idx).collect()
+    }
+
+    /// Number of chunks to download to repair one chunk: the rest of its group and its parity.
+    pub fn get_num_repair_downloads(&self) -> usize {
+        self.group_size
+    }
+}
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
+    /// Local parity chunks, one column per group.
+    pub fn lrc_encode_parities(&self, layout: &LrcLayout, data_coded: &FileMatrix<E::Fr>) -> FileMatrix<E::Fr> {
+        assert!(data_coded.width() == self.n);
+        let mut parities = FileMatrix::zeros(data_coded.height(), layout.num_groups());
+
+        let timer = start_timer!(|| "Encoding local parities");
+        for group in 0..layout.num_groups() {
+            let parity = parities.column_mut(group);
+            for idx in layout.members(group) {
+                for (p, v) in parity.iter_mut().zip(data_coded.column(idx)) {
+                    *p += v;
+                }
+            }
+        }
+        end_timer!(timer);
+
+        parities
+    }
+
+
+    /// Commitments to the local parity chunks for every segment, derived homomorphically from
+    /// the column commitments as sums of the coded chunk commitments of each group.
+    pub fn lrc_parity_commitments(&self, layout: &LrcLayout, column_commitments: &[C::Commitment]) -> Vec<Vec<C::Commitment>> {
+        assert!(layout.n == self.n);
+
+        let ones = vec![E::Fr::one(); layout.group_size()];
+        self.encode_commitments_all(column_commitments).iter().map(|coded_chunk_commitments| {
+            (0..layout.num_groups()).map(|group| {
+                self.commitment_scheme.combine(&coded_chunk_commitments[layout.members(group)], &ones)
+            }).collect()
+        }).collect()
+    }
+
+
+    /// Check the local parity chunk of `group` against the column commitments.
+    pub fn lrc_verify_parity(&self, layout: &LrcLayout, column_commitments: &[C::Commitment], parity: &[E::Fr], group: usize) -> bool {
+        if parity.len() != self.get_num_rows() || group >= layout.num_groups() {
+            return false;
+        }
+
+        let timer = start_timer!(|| "Verifying local parity");
+        let parity_commitments = self.lrc_parity_commitments(layout, column_commitments);
+        let data_parity = FileMatrix::from_columns(parity.len(), 1, vec![parity.to_vec()]);
+        let ret_val = parity_commitments.iter().enumerate().all(|(s, c)| self.commit_column(&data_parity, s, 0) == c[group]);
+        end_timer!(timer);
+
+        ret_val
+    }
+
+
+    /// Repair the chunk of node `idx_target` from the other chunks of its group (column `c` of
+    /// `data_coded_group` being the chunk of node `idxs_repair_nodes[c]`, see `repair_sources`)
+    /// and the group's local parity. The result can be checked with `repair_verify_chunk`.
+    pub fn lrc_repair_chunk(&self, layout: &LrcLayout, data_coded_group: &FileMatrix<E::Fr>, idxs_repair_nodes: &[usize], parity: &[E::Fr], idx_target: usize) -> Vec<E::Fr> {
+        assert!(data_coded_group.width() == idxs_repair_nodes.len());
+        assert!(parity.len() == data_coded_group.height());
+        let mut idxs_repair_nodes = idxs_repair_nodes.to_vec();
+        idxs_repair_nodes.sort_unstable();
+        assert!(idxs_repair_nodes == layout.repair_sources(idx_target), "Local repair needs exactly the other members of the group");
+
+        let timer = start_timer!(|| "Local repair");
+        let mut chunk = parity.to_vec();
+        for column in data_coded_group.columns() {
+            for (c, v) in chunk.iter_mut().zip(column) {
+                *c -= v;
+            }
+        }
+        end_timer!(timer);
+
+        chunk
+    }
+}
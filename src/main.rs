@@ -1,9 +1,9 @@
 extern crate semiavidpr;
-use semiavidpr::{SemiAvidPr};
+use semiavidpr::{SemiAvidPr, ColumnCommitmentScheme, Kzg, Pedersen, LrcLayout, AuditScheduler, DecodedCheckMode, Curve, CurveVisitor, NamedCurve, Actor, LinkConfig, SimConfig, DispersalCertificate, NodeRegistry, SigningKey};
 
 use ark_ec::{PairingEngine};
-use ark_bls12_381::{Bls12_381};
-use ark_bn254::{Bn254};
+use ark_serialize::{CanonicalSerialize};
+use ark_std::{Zero};
 
 use rand::{Rng};
 use std::time::{Instant};
@@ -29,21 +29,61 @@ struct Args {
     iterations: usize,
 
     /// Pairing-friendly curve to use for experiments
-    #[clap(arg_enum, short, long, default_value_t = CurveArg::Bls12_381)]
-    curve: CurveArg,
+    #[clap(short, long, default_value_t = Curve::Bls12_381, possible_values = Curve::NAMES)]
+    curve: Curve,
+
+    /// Commitment scheme for the columns
+    #[clap(arg_enum, short, long, default_value_t = BackendArg::Kzg)]
+    backend: BackendArg,
+
+    /// Number of length-L segments per column
+    #[clap(short, long, default_value_t = 1)]
+    segments: usize,
+
+    /// Group size of the locally repairable code layout for repair experiments (0 to disable)
+    #[clap(long, default_value_t = 0)]
+    lrc_group_size: usize,
+
+    /// Also check the decoded file against the column commitments, by recommitting and by a random linear combination
+    #[clap(long)]
+    check_decoded: bool,
+
+    /// Also repair a lost chunk from k chunks of the Reed-Solomon code
+    #[clap(long)]
+    repair: bool,
+
+    /// Also audit one round of k storage nodes
+    #[clap(long)]
+    audit: bool,
+
+    /// Also aggregate and verify a dispersal certificate
+    #[clap(long)]
+    certificate: bool,
+
+    /// Also run the whole protocol over a simulated network with the link parameters below
+    #[clap(long)]
+    simulate: bool,
+
+    /// One-way latency of every simulated link, in milliseconds
+    #[clap(long, default_value_t = 50.0)]
+    link_latency_ms: f64,
+
+    /// Bandwidth of every simulated link, in megabits per second
+    #[clap(long, default_value_t = 100.0)]
+    link_bandwidth_mbps: f64,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum, Debug)]
-enum CurveArg {
-    Bls12_381,
-    Bn254,
+enum BackendArg {
+    Kzg,
+    Pedersen,
 }
 
-impl std::fmt::Display for CurveArg {
+impl std::fmt::Display for BackendArg {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            CurveArg::Bls12_381 => write!(f, "bls12-381"),
-            CurveArg::Bn254 => write!(f, "bn254"),
+            BackendArg::Kzg => write!(f, "kzg"),
+            BackendArg::Pedersen => write!(f, "pedersen"),
         }
     }
 }
@@ -70,6 +110,11 @@ struct Measurements {
     runtime_all_row_decodings_seconds: f64,
     runtime_per_row_decoding_seconds: f64,
     runtime_prepare_decoding_seconds: f64,
+    runtime_prepare_decoding_cached_seconds: f64,
+    num_decoder_cache_hits: usize,
+    num_decoder_cache_misses: usize,
+    runtime_check_decoded_recommit_seconds: f64,
+    runtime_check_decoded_rlc_seconds: f64,
 
     num_column_commitments: usize,
     num_row_encodings: usize,
@@ -92,6 +137,32 @@ struct Measurements {
     scenario_sampling_runtime_prover_seconds: f64,
     scenario_sampling_runtime_verifier_seconds: f64,
     scenario_sampling_runtime_proof_size_bytes: usize,
+
+    scenario_repair_rs_num_downloaded_chunks: usize,
+    scenario_repair_rs_communication_bytes: usize,
+    scenario_repair_rs_runtime_seconds: f64,
+    scenario_repair_lrc_num_downloaded_chunks: usize,
+    scenario_repair_lrc_communication_bytes: usize,
+    scenario_repair_lrc_runtime_seconds: f64,
+    scenario_repair_lrc_storage_overhead_bytes: usize,
+
+    scenario_audit_num_audits: usize,
+    scenario_audit_num_failed: usize,
+    scenario_audit_runtime_prover_seconds: f64,
+    scenario_audit_runtime_verifier_seconds: f64,
+    scenario_audit_response_size_bytes: usize,
+
+    scenario_network_dispersal_latency_seconds: f64,
+    scenario_network_retrieval_latency_seconds: f64,
+    scenario_network_sampling_latency_seconds: f64,
+    scenario_network_client_sent_bytes: usize,
+    scenario_network_max_node_cpu_seconds: f64,
+
+    scenario_certificate_quorum: usize,
+    scenario_certificate_runtime_sign_seconds: f64,
+    scenario_certificate_runtime_aggregate_seconds: f64,
+    scenario_certificate_runtime_verify_seconds: f64,
+    scenario_certificate_size_bytes: usize,
 }
 
 impl core::ops::Add for Measurements {
@@ -118,6 +189,11 @@ impl core::ops::Add for Measurements {
             runtime_all_row_decodings_seconds: self.runtime_all_row_decodings_seconds + other.runtime_all_row_decodings_seconds,
             runtime_per_row_decoding_seconds: self.runtime_per_row_decoding_seconds + other.runtime_per_row_decoding_seconds,
             runtime_prepare_decoding_seconds: self.runtime_prepare_decoding_seconds + other.runtime_prepare_decoding_seconds,
+            runtime_prepare_decoding_cached_seconds: self.runtime_prepare_decoding_cached_seconds + other.runtime_prepare_decoding_cached_seconds,
+            num_decoder_cache_hits: self.num_decoder_cache_hits + other.num_decoder_cache_hits,
+            num_decoder_cache_misses: self.num_decoder_cache_misses + other.num_decoder_cache_misses,
+            runtime_check_decoded_recommit_seconds: self.runtime_check_decoded_recommit_seconds + other.runtime_check_decoded_recommit_seconds,
+            runtime_check_decoded_rlc_seconds: self.runtime_check_decoded_rlc_seconds + other.runtime_check_decoded_rlc_seconds,
 
             num_column_commitments: self.num_column_commitments + other.num_column_commitments,
             num_row_encodings: self.num_row_encodings + other.num_row_encodings,
@@ -140,6 +216,32 @@ impl core::ops::Add for Measurements {
             scenario_sampling_runtime_prover_seconds: self.scenario_sampling_runtime_prover_seconds + other.scenario_sampling_runtime_prover_seconds,
             scenario_sampling_runtime_verifier_seconds: self.scenario_sampling_runtime_verifier_seconds + other.scenario_sampling_runtime_verifier_seconds,
             scenario_sampling_runtime_proof_size_bytes: self.scenario_sampling_runtime_proof_size_bytes + other.scenario_sampling_runtime_proof_size_bytes,
+
+            scenario_repair_rs_num_downloaded_chunks: self.scenario_repair_rs_num_downloaded_chunks + other.scenario_repair_rs_num_downloaded_chunks,
+            scenario_repair_rs_communication_bytes: self.scenario_repair_rs_communication_bytes + other.scenario_repair_rs_communication_bytes,
+            scenario_repair_rs_runtime_seconds: self.scenario_repair_rs_runtime_seconds + other.scenario_repair_rs_runtime_seconds,
+            scenario_repair_lrc_num_downloaded_chunks: self.scenario_repair_lrc_num_downloaded_chunks + other.scenario_repair_lrc_num_downloaded_chunks,
+            scenario_repair_lrc_communication_bytes: self.scenario_repair_lrc_communication_bytes + other.scenario_repair_lrc_communication_bytes,
+            scenario_repair_lrc_runtime_seconds: self.scenario_repair_lrc_runtime_seconds + other.scenario_repair_lrc_runtime_seconds,
+            scenario_repair_lrc_storage_overhead_bytes: self.scenario_repair_lrc_storage_overhead_bytes + other.scenario_repair_lrc_storage_overhead_bytes,
+
+            scenario_audit_num_audits: self.scenario_audit_num_audits + other.scenario_audit_num_audits,
+            scenario_audit_num_failed: self.scenario_audit_num_failed + other.scenario_audit_num_failed,
+            scenario_audit_runtime_prover_seconds: self.scenario_audit_runtime_prover_seconds + other.scenario_audit_runtime_prover_seconds,
+            scenario_audit_runtime_verifier_seconds: self.scenario_audit_runtime_verifier_seconds + other.scenario_audit_runtime_verifier_seconds,
+            scenario_audit_response_size_bytes: self.scenario_audit_response_size_bytes + other.scenario_audit_response_size_bytes,
+
+            scenario_network_dispersal_latency_seconds: self.scenario_network_dispersal_latency_seconds + other.scenario_network_dispersal_latency_seconds,
+            scenario_network_retrieval_latency_seconds: self.scenario_network_retrieval_latency_seconds + other.scenario_network_retrieval_latency_seconds,
+            scenario_network_sampling_latency_seconds: self.scenario_network_sampling_latency_seconds + other.scenario_network_sampling_latency_seconds,
+            scenario_network_client_sent_bytes: self.scenario_network_client_sent_bytes + other.scenario_network_client_sent_bytes,
+            scenario_network_max_node_cpu_seconds: self.scenario_network_max_node_cpu_seconds + other.scenario_network_max_node_cpu_seconds,
+
+            scenario_certificate_quorum: self.scenario_certificate_quorum + other.scenario_certificate_quorum,
+            scenario_certificate_runtime_sign_seconds: self.scenario_certificate_runtime_sign_seconds + other.scenario_certificate_runtime_sign_seconds,
+            scenario_certificate_runtime_aggregate_seconds: self.scenario_certificate_runtime_aggregate_seconds + other.scenario_certificate_runtime_aggregate_seconds,
+            scenario_certificate_runtime_verify_seconds: self.scenario_certificate_runtime_verify_seconds + other.scenario_certificate_runtime_verify_seconds,
+            scenario_certificate_size_bytes: self.scenario_certificate_size_bytes + other.scenario_certificate_size_bytes,
         }
     }
 }
@@ -168,6 +270,11 @@ impl core::ops::Div<usize> for Measurements {
             runtime_all_row_decodings_seconds: self.runtime_all_row_decodings_seconds / (rhs as f64),
             runtime_per_row_decoding_seconds: self.runtime_per_row_decoding_seconds / (rhs as f64),
             runtime_prepare_decoding_seconds: self.runtime_prepare_decoding_seconds / (rhs as f64),
+            runtime_prepare_decoding_cached_seconds: self.runtime_prepare_decoding_cached_seconds / (rhs as f64),
+            num_decoder_cache_hits: self.num_decoder_cache_hits / rhs,
+            num_decoder_cache_misses: self.num_decoder_cache_misses / rhs,
+            runtime_check_decoded_recommit_seconds: self.runtime_check_decoded_recommit_seconds / (rhs as f64),
+            runtime_check_decoded_rlc_seconds: self.runtime_check_decoded_rlc_seconds / (rhs as f64),
 
             num_column_commitments: self.num_column_commitments / rhs,
             num_row_encodings: self.num_row_encodings / rhs,
@@ -190,6 +297,32 @@ impl core::ops::Div<usize> for Measurements {
             scenario_sampling_runtime_prover_seconds: self.scenario_sampling_runtime_prover_seconds / (rhs as f64),
             scenario_sampling_runtime_verifier_seconds: self.scenario_sampling_runtime_verifier_seconds / (rhs as f64),
             scenario_sampling_runtime_proof_size_bytes: self.scenario_sampling_runtime_proof_size_bytes / rhs,
+
+            scenario_repair_rs_num_downloaded_chunks: self.scenario_repair_rs_num_downloaded_chunks / rhs,
+            scenario_repair_rs_communication_bytes: self.scenario_repair_rs_communication_bytes / rhs,
+            scenario_repair_rs_runtime_seconds: self.scenario_repair_rs_runtime_seconds / (rhs as f64),
+            scenario_repair_lrc_num_downloaded_chunks: self.scenario_repair_lrc_num_downloaded_chunks / rhs,
+            scenario_repair_lrc_communication_bytes: self.scenario_repair_lrc_communication_bytes / rhs,
+            scenario_repair_lrc_runtime_seconds: self.scenario_repair_lrc_runtime_seconds / (rhs as f64),
+            scenario_repair_lrc_storage_overhead_bytes: self.scenario_repair_lrc_storage_overhead_bytes / rhs,
+
+            scenario_audit_num_audits: self.scenario_audit_num_audits / rhs,
+            scenario_audit_num_failed: self.scenario_audit_num_failed / rhs,
+            scenario_audit_runtime_prover_seconds: self.scenario_audit_runtime_prover_seconds / (rhs as f64),
+            scenario_audit_runtime_verifier_seconds: self.scenario_audit_runtime_verifier_seconds / (rhs as f64),
+            scenario_audit_response_size_bytes: self.scenario_audit_response_size_bytes / rhs,
+
+            scenario_network_dispersal_latency_seconds: self.scenario_network_dispersal_latency_seconds / (rhs as f64),
+            scenario_network_retrieval_latency_seconds: self.scenario_network_retrieval_latency_seconds / (rhs as f64),
+            scenario_network_sampling_latency_seconds: self.scenario_network_sampling_latency_seconds / (rhs as f64),
+            scenario_network_client_sent_bytes: self.scenario_network_client_sent_bytes / rhs,
+            scenario_network_max_node_cpu_seconds: self.scenario_network_max_node_cpu_seconds / (rhs as f64),
+
+            scenario_certificate_quorum: self.scenario_certificate_quorum / rhs,
+            scenario_certificate_runtime_sign_seconds: self.scenario_certificate_runtime_sign_seconds / (rhs as f64),
+            scenario_certificate_runtime_aggregate_seconds: self.scenario_certificate_runtime_aggregate_seconds / (rhs as f64),
+            scenario_certificate_runtime_verify_seconds: self.scenario_certificate_runtime_verify_seconds / (rhs as f64),
+            scenario_certificate_size_bytes: self.scenario_certificate_size_bytes / rhs,
         }
     }
 }
@@ -201,8 +334,22 @@ impl core::ops::Div<usize> for Measurements {
 //     dummy
 // }
 
+/// Files that the retrieval experiment retrieves from the same storage nodes, all but the first
+/// with their decoding matrix from the cache.
+const NUM_FILES_RETRIEVED: usize = 3;
+
+/// Optional scenarios of the dispersal experiment, whose measurements stay zero if disabled.
+struct Scenarios<'s> {
+    lrc_group_size: usize,
+    check_decoded: bool,
+    repair: bool,
+    audit: bool,
+    certificate: bool,
+    simulation: Option<&'s SimConfig>,
+}
+
 #[allow(non_snake_case)]
-fn run_dispersal_experiment<R: Rng + ?Sized, E: PairingEngine>(mut rng: &mut R, n: usize, k: usize, L: usize) -> Measurements {
+fn run_dispersal_experiment<R: Rng + ?Sized, E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>>(mut rng: &mut R, n: usize, k: usize, L: usize, segments: usize, scenarios: &Scenarios) -> Measurements {
     let mut measurements = Measurements::default();
     measurements.num_measurements = 1;
 
@@ -212,7 +359,7 @@ fn run_dispersal_experiment<R: Rng + ?Sized, E: PairingEngine>(mut rng: &mut R,
     // setup
 
     let timer_begin = Instant::now();
-    let scheme = SemiAvidPr::<E>::setup(&mut rng, n, k, L);
+    let scheme = SemiAvidPr::<E, C>::setup_with_segments(&mut rng, n, k, L, segments);
     measurements.runtime_setup_seconds = timer_begin.elapsed().as_secs_f64();
 
     measurements.net_file_size_bytes = scheme.get_filesize_in_bytes() as usize;
@@ -258,9 +405,13 @@ fn run_dispersal_experiment<R: Rng + ?Sized, E: PairingEngine>(mut rng: &mut R,
 
     // BOOKKEEPING
 
-    measurements.size_file_uncoded_bytes = std::mem::size_of::<E::Fr>() * k * L;
-    measurements.size_column_commitments_bytes = std::mem::size_of::<E::G1Affine>() * k;
-    measurements.size_file_coded_bytes = std::mem::size_of::<E::Fr>() * n * L;
+    // sizes on the wire, which differ between curves (and from the in-memory sizes)
+    let size_entry_bytes = E::Fr::zero().serialized_size();
+    let size_column_commitment_bytes = column_commitments[0].serialized_size();
+
+    measurements.size_file_uncoded_bytes = size_entry_bytes * k * L * segments;
+    measurements.size_column_commitments_bytes = size_column_commitment_bytes * k * segments;
+    measurements.size_file_coded_bytes = size_entry_bytes * n * L * segments;
 
     measurements.scenario_disperse_runtime_client_seconds = measurements.runtime_all_column_commitments_seconds + measurements.runtime_all_row_encodings_seconds;
     measurements.scenario_disperse_runtime_node_seconds = measurements.runtime_per_chunk_verification_seconds;
@@ -299,13 +450,40 @@ fn run_dispersal_experiment<R: Rng + ?Sized, E: PairingEngine>(mut rng: &mut R,
 
     // black_box(file_uncoded_downloaded);
 
-    for row in 0..L {
+    // check the decoded file against the column commitments, as a retriever without the original would
+
+    if scenarios.check_decoded {
+        let timer_begin = Instant::now();
+        scheme.retrieve_check_decoded(&mut rng, &column_commitments, &file_uncoded_downloaded, DecodedCheckMode::Recommit).expect("Decoded file should match the column commitments!");
+        measurements.runtime_check_decoded_recommit_seconds = timer_begin.elapsed().as_secs_f64();
+
+        let timer_begin = Instant::now();
+        scheme.retrieve_check_decoded(&mut rng, &column_commitments, &file_uncoded_downloaded, DecodedCheckMode::RandomLinearCombination).expect("Decoded file should match the column commitments!");
+        measurements.runtime_check_decoded_rlc_seconds = timer_begin.elapsed().as_secs_f64();
+    }
+
+    for row in 0..scheme.get_num_rows() {
         for col in 0..k {
-            assert!(file_uncoded[row][col] == file_uncoded_downloaded[row][col]);
+            assert!(file_uncoded[(row, col)] == file_uncoded_downloaded[(row, col)]);
         }
     }
 
 
+    // retrieve further files from the same storage nodes, whose decode preparations the cache serves
+
+    for _ in 1..NUM_FILES_RETRIEVED {
+        let file_uncoded_further = scheme.generate_random_file(&mut rng);
+        let file_coded_further_downloaded = scheme.retrieve_download_chunks(&scheme.disperse_encode_rows(&file_uncoded_further), &idxs_download_nodes);
+
+        let timer_begin = Instant::now();
+        let decoder_aux_further = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+        measurements.runtime_prepare_decoding_cached_seconds += timer_begin.elapsed().as_secs_f64() / ((NUM_FILES_RETRIEVED - 1) as f64);
+
+        assert!(scheme.retrieve_decode_rows(&file_coded_further_downloaded, &decoder_aux_further) == file_uncoded_further);
+    }
+    (measurements.num_decoder_cache_hits, measurements.num_decoder_cache_misses) = scheme.get_decoder_cache_stats();
+
+
     // BOOKKEEPING
 
     measurements.scenario_retrieve_runtime_client_seconds = measurements.runtime_all_downloaded_chunk_verifications_seconds + measurements.runtime_prepare_decoding_seconds + measurements.runtime_all_row_decodings_seconds;
@@ -313,18 +491,18 @@ fn run_dispersal_experiment<R: Rng + ?Sized, E: PairingEngine>(mut rng: &mut R,
 
     // SAMPLING (OPENING ENTRIES)
 
-    measurements.scenario_sampling_num_openings = std::cmp::min(k, L);
+    measurements.scenario_sampling_num_openings = std::cmp::min(k, scheme.get_num_rows());
     for idx in 0..measurements.scenario_sampling_num_openings {
         let timer_begin = Instant::now();
         let opening = scheme.sampling_open_entry(&column_commitments, &file_uncoded, idx, idx);
         measurements.scenario_sampling_runtime_prover_seconds += timer_begin.elapsed().as_secs_f64();
 
         measurements.scenario_sampling_runtime_proof_size_bytes = 0;
-        measurements.scenario_sampling_runtime_proof_size_bytes += std::mem::size_of::<E::Fr>(); // value
+        measurements.scenario_sampling_runtime_proof_size_bytes += size_entry_bytes; // value
         measurements.scenario_sampling_runtime_proof_size_bytes += std::mem::size_of::<usize>(); // row
         measurements.scenario_sampling_runtime_proof_size_bytes += std::mem::size_of::<usize>(); // col
         measurements.scenario_sampling_runtime_proof_size_bytes += measurements.size_column_commitments_bytes; // column_commitments
-        measurements.scenario_sampling_runtime_proof_size_bytes += std::mem::size_of::<E::G1Affine>(); // KZG proof
+        measurements.scenario_sampling_runtime_proof_size_bytes += C::proof_size(&opening.4); // opening proof
 
         let timer_begin = Instant::now();
         if !scheme.sampling_verify_entry(opening) {
@@ -338,10 +516,166 @@ fn run_dispersal_experiment<R: Rng + ?Sized, E: PairingEngine>(mut rng: &mut R,
     // black_box(openings);
 
 
+    // REPAIR (REBUILDING A LOST CHUNK)
+
+    let idx_target = 0;
+    let size_chunk_bytes = size_entry_bytes * scheme.get_num_rows();
+
+    // from k chunks of the Reed-Solomon code
+
+    if scenarios.repair {
+        let idxs_repair_nodes: Vec<usize> = (1..=k).collect();
+        let file_coded_repair = scheme.retrieve_download_chunks(&file_coded, &idxs_repair_nodes);
+        let timer_begin = Instant::now();
+        let chunk_repaired = scheme.repair_compute_chunk(&file_coded_repair, &idxs_repair_nodes, idx_target);
+        if !scheme.repair_verify_chunk(&column_commitments, &chunk_repaired, idx_target) {
+            panic!("Verification of repaired chunk should not fail!");
+        }
+        measurements.scenario_repair_rs_runtime_seconds = timer_begin.elapsed().as_secs_f64();
+        measurements.scenario_repair_rs_num_downloaded_chunks = k;
+        measurements.scenario_repair_rs_communication_bytes = k * size_chunk_bytes;
+    }
+
+    // from the local group of the locally repairable code layout
+
+    if scenarios.lrc_group_size > 0 {
+        let layout = LrcLayout::new(n, scenarios.lrc_group_size);
+        let file_parities = scheme.lrc_encode_parities(&layout, &file_coded);
+        measurements.scenario_repair_lrc_storage_overhead_bytes = layout.num_groups() * size_chunk_bytes;
+
+        let idxs_repair_nodes = layout.repair_sources(idx_target);
+        let file_coded_repair = scheme.retrieve_download_chunks(&file_coded, &idxs_repair_nodes);
+        let timer_begin = Instant::now();
+        let chunk_repaired = scheme.lrc_repair_chunk(&layout, &file_coded_repair, &idxs_repair_nodes, file_parities.column(layout.group_of(idx_target)), idx_target);
+        if !scheme.repair_verify_chunk(&column_commitments, &chunk_repaired, idx_target) {
+            panic!("Verification of repaired chunk should not fail!");
+        }
+        measurements.scenario_repair_lrc_runtime_seconds = timer_begin.elapsed().as_secs_f64();
+        measurements.scenario_repair_lrc_num_downloaded_chunks = layout.get_num_repair_downloads();
+        measurements.scenario_repair_lrc_communication_bytes = layout.get_num_repair_downloads() * size_chunk_bytes;
+    }
+
+
+    // AUDITS (PROOFS OF RETRIEVABILITY)
+
+    // one round of audits of k nodes, the first of which has lost its chunk
+
+    if scenarios.audit {
+        let mut file_stored = file_coded.clone();
+        for v in file_stored.column_mut(0) {
+            *v = E::Fr::zero();
+        }
+
+        let mut scheduler = AuditScheduler::new(n, k);
+        for idx in scheduler.next_round() {
+            let challenge = scheme.audit_challenge_point(&mut rng);
+
+            let timer_begin = Instant::now();
+            let response = scheme.audit_respond(file_stored.column(idx), &challenge).unwrap();
+            measurements.scenario_audit_runtime_prover_seconds += timer_begin.elapsed().as_secs_f64();
+
+            measurements.scenario_audit_response_size_bytes = response.values.len() * size_entry_bytes + response.proofs.iter().map(C::proof_size).sum::<usize>();
+
+            let timer_begin = Instant::now();
+            let passed = scheme.audit_verify(&column_commitments, idx, &challenge, &response);
+            measurements.scenario_audit_runtime_verifier_seconds += timer_begin.elapsed().as_secs_f64();
+
+            if passed == (idx == 0) {
+                panic!("Audit should fail exactly for the node that lost its chunk!");
+            }
+            scheduler.record(idx, passed);
+        }
+        measurements.scenario_audit_num_audits = scheduler.total().num_audits;
+        measurements.scenario_audit_num_failed = scheduler.total().num_failed;
+        measurements.scenario_audit_runtime_prover_seconds /= measurements.scenario_audit_num_audits as f64;
+        measurements.scenario_audit_runtime_verifier_seconds /= measurements.scenario_audit_num_audits as f64;
+    }
+
+
+    // DISPERSAL CERTIFICATE (n - t SIGNATURES, FOR t = (n - k) / 2 BYZANTINE NODES)
+
+    if scenarios.certificate {
+        let signing_keys: Vec<SigningKey<E>> = (0..n).map(|_| SigningKey::generate(&mut rng)).collect();
+        let mut registry = NodeRegistry::new();
+        for signing_key in signing_keys.iter() {
+            registry.register(signing_key.public_key(), &signing_key.prove_possession()).unwrap();
+        }
+        let quorum = scheme.certificate_quorum((n - k) / 2);
+        measurements.scenario_certificate_quorum = quorum;
+
+        let timer_begin = Instant::now();
+        let file_id = scheme.file_id(&column_commitments);
+        let signature = signing_keys[0].sign(&file_id);
+        measurements.scenario_certificate_runtime_sign_seconds = timer_begin.elapsed().as_secs_f64();
+
+        let mut signatures = vec![(0, signature)];
+        signatures.extend((1..quorum).map(|i| (i, signing_keys[i].sign(&file_id))));
+
+        let timer_begin = Instant::now();
+        let certificate = DispersalCertificate::aggregate(&registry, &file_id, &signatures, quorum).unwrap();
+        measurements.scenario_certificate_runtime_aggregate_seconds = timer_begin.elapsed().as_secs_f64();
+
+        let timer_begin = Instant::now();
+        let valid = certificate.verify(&registry, &scheme.file_id(&column_commitments), quorum);
+        measurements.scenario_certificate_runtime_verify_seconds = timer_begin.elapsed().as_secs_f64();
+
+        if !valid {
+            panic!("Dispersal certificate should verify!");
+        }
+        measurements.scenario_certificate_size_bytes = certificate.serialized_size();
+    }
+
+
+    // NETWORK (WHOLE PROTOCOL OVER SIMULATED LINKS)
+
+    if let Some(config) = scenarios.simulation {
+        let report = scheme.simulate_network(&mut rng, config);
+        if !report.retrieved_correctly || report.num_samples_failed > 0 {
+            panic!("Simulated retrieval and sampling should not fail!");
+        }
+
+        measurements.scenario_network_dispersal_latency_seconds = report.dispersal_latency_seconds.unwrap();
+        measurements.scenario_network_retrieval_latency_seconds = report.retrieval_latency_seconds.unwrap();
+        measurements.scenario_network_sampling_latency_seconds = report.sampling_latency_seconds.unwrap();
+        measurements.scenario_network_client_sent_bytes = report.bytes_sent(Actor::Client);
+        measurements.scenario_network_max_node_cpu_seconds = (0..n).map(|i| report.cpu_seconds[&Actor::Node(i)]).fold(0.0, f64::max);
+    }
+
+
     measurements
 }
 
 
+/// One run of the dispersal experiment over the curve it visits.
+struct Experiment<'r, R: Rng + ?Sized> {
+    rng: &'r mut R,
+    args: &'r Args,
+}
+
+impl<R: Rng + ?Sized> CurveVisitor for Experiment<'_, R> {
+    type Output = Measurements;
+
+    fn visit<E: NamedCurve>(self) -> Measurements {
+        let args = self.args;
+        let mut simulation = SimConfig::new(args.n, LinkConfig::new(args.link_latency_ms * 1e-3, args.link_bandwidth_mbps * 1e6 / 8.0));
+        simulation.charge_cpu_time = true;
+        let scenarios = Scenarios {
+            lrc_group_size: args.lrc_group_size,
+            check_decoded: args.check_decoded,
+            repair: args.repair,
+            audit: args.audit,
+            certificate: args.certificate,
+            simulation: if args.simulate { Some(&simulation) } else { None },
+        };
+
+        match args.backend {
+            BackendArg::Kzg => run_dispersal_experiment::<_, E, Kzg<E>>(self.rng, args.n, args.k, args.L, args.segments, &scenarios),
+            BackendArg::Pedersen => run_dispersal_experiment::<_, E, Pedersen<E::G1Affine>>(self.rng, args.n, args.k, args.L, args.segments, &scenarios),
+        }
+    }
+}
+
+
 fn main() {
     let mut rng = ark_std::rand::thread_rng();
 
@@ -357,32 +691,51 @@ fn main() {
         .exit();
     }
 
+    if args.lrc_group_size > 0 && !args.n.is_multiple_of(args.lrc_group_size) {
+        let mut app = Args::into_app();
+        app.error(
+            ErrorKind::InvalidValue,
+            "N has to be a multiple of the LRC group size",
+        )
+        .exit();
+    }
+
+    if args.segments == 0 {
+        let mut app = Args::into_app();
+        app.error(
+            ErrorKind::InvalidValue,
+            "Number of segments has to be at least 1",
+        )
+        .exit();
+    }
+
     assert!(args.n.is_power_of_two());
     assert!(args.L.is_power_of_two());
 
     let mut measurement = Measurements::default();
     for _iter in 0..args.iterations {
-        measurement = measurement + match args.curve {
-            CurveArg::Bls12_381 => run_dispersal_experiment::<_, Bls12_381>(&mut rng, args.n, args.k, args.L),
-            CurveArg::Bn254 => run_dispersal_experiment::<_, Bn254>(&mut rng, args.n, args.k, args.L),
-        };
+        measurement = measurement + args.curve.visit(Experiment { rng: &mut rng, args: &args });
     }
 
     assert_eq!(args.iterations, measurement.num_measurements);
     measurement = measurement / measurement.num_measurements;
     println!("# {:?}", measurement);
 
-    println!("{} {} {} {} {}  \
+    println!("{} {} {} {} {} {}  \
         {}  \
         {:.6} {:.6}  \
         {:.6} {:.6} {:.6} {:.6} {:.6} {:.6}  \
-        {:.6} {:.6} {:.6} {:.6} {:.6}  \
+        {:.6} {:.6} {:.6} {:.6} {:.6} {:.6} {} {} {:.6} {:.6}  \
         {} {} {} {} {}  \
         {} {} {}  \
         {:.6} {:.6} {} {}  \
         {:.6}  \
-        {} {:.6} {:.6} {}",
-        args.n, args.k, args.L, args.iterations, args.curve,
+        {} {:.6} {:.6} {}  \
+        {} {} {:.6} {} {} {:.6} {}  \
+        {} {} {:.6} {:.6} {}  \
+        {:.6} {:.6} {:.6} {} {:.6}  \
+        {} {:.6} {:.6} {:.6} {}",
+        args.n, args.k, args.L, args.iterations, args.curve, args.backend,
 
         measurement.net_file_size_bytes,
 
@@ -401,6 +754,11 @@ fn main() {
         measurement.runtime_all_row_decodings_seconds,
         measurement.runtime_per_row_decoding_seconds,
         measurement.runtime_prepare_decoding_seconds,
+        measurement.runtime_prepare_decoding_cached_seconds,
+        measurement.num_decoder_cache_hits,
+        measurement.num_decoder_cache_misses,
+        measurement.runtime_check_decoded_recommit_seconds,
+        measurement.runtime_check_decoded_rlc_seconds,
 
         measurement.num_column_commitments,
         measurement.num_row_encodings,
@@ -423,5 +781,31 @@ fn main() {
         measurement.scenario_sampling_runtime_prover_seconds,
         measurement.scenario_sampling_runtime_verifier_seconds,
         measurement.scenario_sampling_runtime_proof_size_bytes,
+
+        measurement.scenario_repair_rs_num_downloaded_chunks,
+        measurement.scenario_repair_rs_communication_bytes,
+        measurement.scenario_repair_rs_runtime_seconds,
+        measurement.scenario_repair_lrc_num_downloaded_chunks,
+        measurement.scenario_repair_lrc_communication_bytes,
+        measurement.scenario_repair_lrc_runtime_seconds,
+        measurement.scenario_repair_lrc_storage_overhead_bytes,
+
+        measurement.scenario_audit_num_audits,
+        measurement.scenario_audit_num_failed,
+        measurement.scenario_audit_runtime_prover_seconds,
+        measurement.scenario_audit_runtime_verifier_seconds,
+        measurement.scenario_audit_response_size_bytes,
+
+        measurement.scenario_network_dispersal_latency_seconds,
+        measurement.scenario_network_retrieval_latency_seconds,
+        measurement.scenario_network_sampling_latency_seconds,
+        measurement.scenario_network_client_sent_bytes,
+        measurement.scenario_network_max_node_cpu_seconds,
+
+        measurement.scenario_certificate_quorum,
+        measurement.scenario_certificate_runtime_sign_seconds,
+        measurement.scenario_certificate_runtime_aggregate_seconds,
+        measurement.scenario_certificate_runtime_verify_seconds,
+        measurement.scenario_certificate_size_bytes,
     );
 }
@@ -0,0 +1,124 @@
+use ark_ec::{PairingEngine};
+use ark_serialize::{CanonicalSerialize, CanonicalDeserialize};
+use ark_std::{Zero, start_timer, end_timer};
+
+use memmap2::{Mmap};
+
+use std::fs::{File};
+use std::io::{self, Write, BufWriter};
+use std::path::{Path, PathBuf};
+
+use crate::{SemiAvidPr, ColumnCommitmentScheme, FileMatrix};
+
+
+// On disk, a file is its `FileMatrix` layout (column-major) of canonically serialized field
+// elements, and the chunk of node `i` is its coded column in `chunk-<i>.bin`.
+
+fn serialized_size<E: PairingEngine>() -> usize {
+    E::Fr::zero().serialized_size()
+}
+
+fn invalid_data<T: ToString>(err: T) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+pub fn path_chunk(dir: &Path, idx: usize) -> PathBuf {
+    dir.join(format!("chunk-{}.bin", idx))
+}
+
+/// Write a file in the on-disk format expected by `disperse_out_of_core`.
+pub fn write_file_matrix<E: PairingEngine>(path: &Path, data: &FileMatrix<E::Fr>) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for column in data.columns() {
+        for v in column {
+            v.serialize(&mut writer).map_err(invalid_data)?;
+        }
+    }
+    writer.flush()
+}
+
+/// Read the chunk that `disperse_out_of_core` wrote for node `idx`.
+pub fn read_chunk<E: PairingEngine>(dir: &Path, idx: usize) -> io::Result<Vec<E::Fr>> {
+    let bytes = std::fs::read(path_chunk(dir, idx))?;
+    bytes.chunks(serialized_size::<E>()).map(|b| E::Fr::deserialize(b).map_err(invalid_data)).collect()
+}
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
+    fn read_column_range(&self, input: &[u8], col: usize, rows: std::ops::Range<usize>) -> io::Result<Vec<E::Fr>> {
+        let size = serialized_size::<E>();
+        let offset = col * self.get_num_rows() * size;
+        input[offset + rows.start*size..offset + rows.end*size].chunks(size).map(|b| E::Fr::deserialize(b).map_err(invalid_data)).collect()
+    }
+
+
+    /// Disperse a file that does not fit in memory: the input (see `write_file_matrix`) is
+    /// memory-mapped, column commitments are computed one column segment at a time, and rows
+    /// are encoded in blocks whose coded entries are appended to per-node chunk files in
+    /// `dir_output`. Apart from the setup, at most `memory_budget_bytes` are held at once.
+    pub fn disperse_out_of_core(&self, path_input: &Path, dir_output: &Path, memory_budget_bytes: usize) -> io::Result<Vec<C::Commitment>> {
+        let size = serialized_size::<E>();
+        let num_rows = self.get_num_rows();
+
+        // a column segment is held as evaluations and as coefficients while committing
+        let memory_per_column_segment = 2 * self.L * std::mem::size_of::<E::Fr>();
+        // a row is held uncoded and coded (each also transposed during encoding), and serialized
+        let memory_per_row = 2 * (self.k + self.n) * std::mem::size_of::<E::Fr>() + self.n * size;
+        if memory_budget_bytes < memory_per_column_segment || memory_budget_bytes < memory_per_row {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Memory budget of {} bytes is too small, need at least {} bytes", memory_budget_bytes, std::cmp::max(memory_per_column_segment, memory_per_row))));
+        }
+        let rows_per_block = std::cmp::min(memory_budget_bytes / memory_per_row, num_rows);
+
+        let file_input = File::open(path_input)?;
+        // SAFETY: the input is only read, and must not be modified concurrently by other processes
+        let input = unsafe { Mmap::map(&file_input)? };
+        if input.len() != num_rows * self.k * size {
+            return Err(invalid_data(format!("Input has {} bytes, expected {}", input.len(), num_rows * self.k * size)));
+        }
+
+        let mut column_commitments = Vec::new();
+
+        let timer_outer = start_timer!(|| "Computing column commitments");
+        for s in 0..self.num_segments {
+            for i in 0..self.k {
+                let timer_inner = start_timer!(|| format!("Segment {} column {}", s, i));
+
+                column_commitments.push(self.commitment_scheme.commit(&self.read_column_range(&input, i, s*self.L..(s+1)*self.L)?));
+
+                end_timer!(timer_inner);
+            }
+        }
+        end_timer!(timer_outer);
+
+        std::fs::create_dir_all(dir_output)?;
+        // each buffer holds (about) one block of its chunk, the serialized rows of the budget
+        let mut writers = Vec::with_capacity(self.n);
+        for idx in 0..self.n {
+            writers.push(BufWriter::with_capacity(rows_per_block * size, File::create(path_chunk(dir_output, idx))?));
+        }
+
+        let timer_outer = start_timer!(|| "Encoding rows");
+        for block_start in (0..num_rows).step_by(rows_per_block) {
+            let block = block_start..std::cmp::min(block_start + rows_per_block, num_rows);
+            let timer_inner = start_timer!(|| format!("Rows {}..{}", block.start, block.end));
+
+            let columns = (0..self.k).map(|i| self.read_column_range(&input, i, block.clone())).collect::<io::Result<Vec<Vec<E::Fr>>>>()?;
+            let block_uncoded = FileMatrix::from_columns(block.len(), self.k, columns);
+            let block_coded = self.disperse_encode_rows(&block_uncoded);
+
+            for (writer, column) in writers.iter_mut().zip(block_coded.columns()) {
+                for v in column {
+                    v.serialize(&mut *writer).map_err(invalid_data)?;
+                }
+            }
+
+            end_timer!(timer_inner);
+        }
+        for writer in writers.iter_mut() {
+            writer.flush()?;
+        }
+        end_timer!(timer_outer);
+
+        Ok(column_commitments)
+    }
+}
@@ -0,0 +1,212 @@
+use ark_ec::{ProjectiveCurve, PairingEngine, msm::{VariableBaseMSM}};
+use ark_ff::fields::{PrimeField};
+use ark_poly::{
+    EvaluationDomain, GeneralEvaluationDomain,
+    polynomial::univariate::{DensePolynomial},
+    evaluations::univariate::{Evaluations},
+};
+use ark_poly_commit::{
+    kzg10::{KZG10, Powers, VerifierKey, Proof},
+};
+use ark_serialize::{CanonicalSerialize, CanonicalDeserialize, Read, Write, SerializationError};
+use ark_std::{start_timer, end_timer};
+
+use rand::{Rng};
+
+use crate::{SemiAvidPr, Kzg, FileMatrix, Opening, encode_rows};
+
+
+/// Public parameters of a dispersing client: the commitment key (in monomial basis, for
+/// opening proofs, and in Lagrange basis, for column commitments) and both evaluation domains.
+#[allow(non_snake_case)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ClientParams<E: PairingEngine> {
+    n: usize,
+    k: usize,
+    L: usize,
+    num_segments: usize,
+
+    domain_polycommit: GeneralEvaluationDomain<E::Fr>,
+    domain_encoding: GeneralEvaluationDomain<E::Fr>,
+
+    powers_of_g: Vec<E::G1Affine>,
+    ck_lagrange: Vec<E::G1Affine>,
+}
+
+/// Public parameters of storage node `idx`: enough to check its own chunk against the column
+/// commitments, i.e., the Lagrange-basis commitment key and the node's encoding vector.
+#[allow(non_snake_case)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct NodeParams<E: PairingEngine> {
+    idx: usize,
+    k: usize,
+    L: usize,
+    num_segments: usize,
+
+    ck_lagrange: Vec<E::G1Affine>,
+    encoding_coefficients: Vec<E::Fr>,
+}
+
+/// Public parameters of a light sampler: the verifier key, of constant size.
+#[allow(non_snake_case)]
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SamplerParams<E: PairingEngine> {
+    k: usize,
+    L: usize,
+    num_segments: usize,
+
+    domain_polycommit: GeneralEvaluationDomain<E::Fr>,
+    vk: VerifierKey<E>,
+}
+
+
+impl<E: PairingEngine> SemiAvidPr<E> {
+    pub fn client_params(&self) -> ClientParams<E> {
+        ClientParams {
+            n: self.n, k: self.k, L: self.L, num_segments: self.num_segments,
+            domain_polycommit: self.domain_polycommit,
+            domain_encoding: self.domain_encoding,
+            powers_of_g: self.commitment_scheme.ck.powers_of_g.to_vec(),
+            ck_lagrange: self.commitment_scheme.ck_lagrange.clone(),
+        }
+    }
+
+    pub fn node_params(&self, idx: usize) -> NodeParams<E> {
+        assert!(idx < self.n);
+        NodeParams {
+            idx, k: self.k, L: self.L, num_segments: self.num_segments,
+            ck_lagrange: self.commitment_scheme.ck_lagrange.clone(),
+            encoding_coefficients: self.encoding_coefficients(idx),
+        }
+    }
+
+    pub fn sampler_params(&self) -> SamplerParams<E> {
+        SamplerParams {
+            k: self.k, L: self.L, num_segments: self.num_segments,
+            domain_polycommit: self.domain_polycommit,
+            vk: self.commitment_scheme.vk.clone(),
+        }
+    }
+}
+
+
+impl<E: PairingEngine> ClientParams<E> {
+    pub fn get_num_rows(&self) -> usize {
+        self.L * self.num_segments
+    }
+
+    fn column_segment<'d>(&self, data: &'d FileMatrix<E::Fr>, segment: usize, idx: usize) -> &'d [E::Fr] {
+        &data.column(idx)[segment*self.L..(segment+1)*self.L]
+    }
+
+
+    /// Same as `SemiAvidPr::disperse_compute_column_commitments`.
+    pub fn disperse_compute_column_commitments(&self, data_uncoded: &FileMatrix<E::Fr>) -> Vec<E::G1Affine> {
+        assert!(data_uncoded.height() == self.get_num_rows() && data_uncoded.width() == self.k);
+
+        let timer = start_timer!(|| "Computing column commitments");
+        let column_commitments = (0..self.num_segments)
+            .flat_map(|s| (0..self.k).map(move |i| (s, i)))
+            .map(|(s, i)| Kzg::<E>::commit_lagrange(&self.ck_lagrange, self.column_segment(data_uncoded, s, i)))
+            .collect();
+        end_timer!(timer);
+
+        column_commitments
+    }
+
+    /// Same as `SemiAvidPr::disperse_encode_rows`.
+    pub fn disperse_encode_rows(&self, data_uncoded: &FileMatrix<E::Fr>) -> FileMatrix<E::Fr> {
+        encode_rows(self.domain_encoding, data_uncoded)
+    }
+
+    /// Same as `SemiAvidPr::sampling_open_entry`.
+    pub fn sampling_open_entry(&self, column_commitments: &[E::G1Affine], data_uncoded: &FileMatrix<E::Fr>, row: usize, col: usize) -> Opening<E::Fr, Kzg<E>> {
+        let (segment, row_in_segment) = (row / self.L, row % self.L);
+
+        let timer = start_timer!(|| "Opening proof");
+        let poly_poly: DensePolynomial<E::Fr> = Evaluations::from_vec_and_domain(self.column_segment(data_uncoded, segment, col).to_vec(), self.domain_polycommit).interpolate();
+        let ck = Powers::<E> {
+            powers_of_g: ark_std::borrow::Cow::Borrowed(&self.powers_of_g),
+            powers_of_gamma_g: ark_std::borrow::Cow::Owned(Vec::new()),
+        };
+        let proof = Kzg::<E>::open_polynomial_with(&ck, &poly_poly, self.domain_polycommit.element(row_in_segment));
+        end_timer!(timer);
+
+        (data_uncoded.get(row, col), row, col, column_commitments.to_vec(), proof)
+    }
+}
+
+
+impl<E: PairingEngine> NodeParams<E> {
+    pub fn get_idx(&self) -> usize {
+        self.idx
+    }
+
+    /// Check this node's chunk (all segments) against the column commitments, like
+    /// `SemiAvidPr::disperse_verify_chunks` does for every node.
+    pub fn disperse_verify_chunk(&self, column_commitments: &[E::G1Affine], chunk: &[E::Fr]) -> bool {
+        if column_commitments.len() != self.k * self.num_segments || chunk.len() != self.L * self.num_segments {
+            return false;
+        }
+
+        let timer = start_timer!(|| format!("Checking coded column {}", self.idx));
+        let ret_val = (0..self.num_segments).all(|s| {
+            let commitment = Kzg::<E>::commit_lagrange(&self.ck_lagrange, &chunk[s*self.L..(s+1)*self.L]);
+            // coded chunk commitment, combining the column commitments by the encoding vector
+            let commitment_check = VariableBaseMSM::multi_scalar_mul(&column_commitments[s*self.k..(s+1)*self.k], &self.encoding_coefficients.iter().map(|x| x.into_repr()).collect::<Vec<_>>()).into_affine();
+            commitment == commitment_check
+        });
+        end_timer!(timer);
+
+        ret_val
+    }
+}
+
+
+impl<E: PairingEngine> SamplerParams<E> {
+    fn opening_point(&self, row: usize) -> E::Fr {
+        self.domain_polycommit.element(row % self.L)
+    }
+
+    fn opened_commitment(&self, row: usize, col: usize, column_commitments: &[E::G1Affine]) -> Option<E::G1Affine> {
+        if row >= self.L * self.num_segments || col >= self.k || column_commitments.len() != self.k * self.num_segments {
+            return None;
+        }
+        Some(column_commitments[(row / self.L)*self.k + col])
+    }
+
+
+    /// Same as `SemiAvidPr::sampling_verify_entry`.
+    pub fn sampling_verify_entry(&self, (value, row, col, column_commitments, proof): Opening<E::Fr, Kzg<E>>) -> bool {
+        let commitment = match self.opened_commitment(row, col, &column_commitments) {
+            Some(commitment) => commitment,
+            None => return false,
+        };
+
+        let timer = start_timer!(|| "Opening check");
+        let ret_val = KZG10::<E, DensePolynomial<E::Fr>>::check(&self.vk, &Kzg::<E>::wrap_commitment(commitment).0, self.opening_point(row), value, &proof).unwrap();
+        end_timer!(timer);
+
+        ret_val
+    }
+
+    /// Same as `SemiAvidPr::sampling_verify_entries`.
+    pub fn sampling_verify_entries<R: Rng + ?Sized>(&self, rng: &mut R, openings: &[Opening<E::Fr, Kzg<E>>]) -> bool {
+        let mut commitments = Vec::with_capacity(openings.len());
+        for (_, row, col, column_commitments, _) in openings.iter() {
+            match self.opened_commitment(*row, *col, column_commitments) {
+                Some(commitment) => commitments.push(Kzg::<E>::wrap_commitment(commitment).0),
+                None => return false,
+            }
+        }
+
+        let timer = start_timer!(|| "Batched opening check");
+        let points: Vec<E::Fr> = openings.iter().map(|(_, row, _, _, _)| self.opening_point(*row)).collect();
+        let values: Vec<E::Fr> = openings.iter().map(|(value, _, _, _, _)| *value).collect();
+        let proofs: Vec<Proof<E>> = openings.iter().map(|(_, _, _, _, proof)| *proof).collect();
+        let ret_val = KZG10::<E, DensePolynomial<E::Fr>>::batch_check(&self.vk, &commitments, &points, &values, &proofs, &mut &mut *rng).unwrap();
+        end_timer!(timer);
+
+        ret_val
+    }
+}
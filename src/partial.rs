@@ -0,0 +1,159 @@
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_poly::{
+    EvaluationDomain,
+    UVPolynomial,
+    polynomial::univariate::{DensePolynomial},
+    evaluations::univariate::{Evaluations},
+};
+use ark_std::{Zero, start_timer, end_timer};
+
+use std::ops::{Range};
+
+use rand::{Rng};
+
+use crate::{SemiAvidPr, Kzg, FileMatrix, Matrix};
+use crate::utils::{vandermonde_inverse, vanishing_polynomial};
+
+
+/// Entries `rows` of the chunk of storage node `idx`, with one multi-point KZG proof against
+/// the node's coded chunk commitment for every segment that the rows touch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowsOpening<E: PairingEngine> {
+    pub idx: usize,
+    pub rows: Range<usize>,
+    pub values: Vec<E::Fr>,
+    pub proofs: Vec<E::G1Affine>,
+}
+
+
+impl<E: PairingEngine> SemiAvidPr<E> {
+    /// Like `setup_with_segments`, with the `L` powers of h in G2 that `retrieve_verify_rows`
+    /// needs, which the default setup leaves out since they are expensive to generate.
+    #[allow(non_snake_case)]
+    pub fn setup_for_partial_retrieval<R: Rng + ?Sized>(rng: &mut R, n: usize, k: usize, L: usize, num_segments: usize) -> Self {
+        Self::setup_with(n, k, L, num_segments, |domain_polycommit| Kzg::setup_with_neg_powers_of_h(rng, domain_polycommit, true))
+    }
+
+    /// Split `rows` into (segment, rows within segment, offset into `rows`).
+    fn split_rows_into_segments(&self, rows: &Range<usize>) -> Vec<(usize, Range<usize>, usize)> {
+        assert!(rows.start < rows.end && rows.end <= self.get_num_rows());
+        (rows.start / self.L..=(rows.end - 1) / self.L).map(|s| {
+            let start = std::cmp::max(rows.start, s*self.L);
+            let end = std::cmp::min(rows.end, (s+1)*self.L);
+            (s, (start - s*self.L)..(end - s*self.L), start - rows.start)
+        }).collect()
+    }
+
+    /// Coefficients of the polynomial of degree less than `rows.len()` through `values` at the
+    /// points of `domain_polycommit` indexed by `rows`, together with those points.
+    fn interpolate_rows(&self, rows: &Range<usize>, values: &[E::Fr]) -> (DensePolynomial<E::Fr>, Vec<E::Fr>) {
+        let points: Vec<E::Fr> = rows.clone().map(|r| self.domain_polycommit.element(r)).collect();
+        let coeffs = vandermonde_inverse(&points).unwrap().transpose().mul_vec(values);
+        (DensePolynomial::from_coefficients_vec(coeffs), points)
+    }
+
+
+    /// Open rows `rows` (possibly spanning several segments) of the chunk of node `idx`, i.e.,
+    /// of column `idx` of `data_coded`.
+    pub fn retrieve_open_rows(&self, data_coded: &FileMatrix<E::Fr>, idx: usize, rows: Range<usize>) -> RowsOpening<E> {
+        let values = data_coded.column(idx)[rows.clone()].to_vec();
+        let mut proofs = Vec::new();
+
+        let timer_outer = start_timer!(|| "Multi-point KZG proofs");
+        for (s, rows_in_segment, offset) in self.split_rows_into_segments(&rows) {
+            let timer_inner = start_timer!(|| format!("Segment {}", s));
+
+            // a complete segment is checked against its commitment directly, without proof
+            if rows_in_segment.len() == self.L {
+                proofs.push(E::G1Affine::zero());
+                end_timer!(timer_inner);
+                continue;
+            }
+
+            let poly_evals = Evaluations::from_vec_and_domain(self.column_segment(data_coded, s, idx).to_vec(), self.domain_polycommit);
+            let poly_poly = poly_evals.interpolate();
+
+            let values_in_segment = &values[offset..offset + rows_in_segment.len()];
+            let (poly_interpolation, points) = self.interpolate_rows(&rows_in_segment, values_in_segment);
+            let poly_vanishing = DensePolynomial::from_coefficients_vec(vanishing_polynomial(&points));
+            let witness_polynomial = &(&poly_poly - &poly_interpolation) / &poly_vanishing;
+            proofs.push(self.commitment_scheme.commit_polynomial(&witness_polynomial));
+
+            end_timer!(timer_inner);
+        }
+        end_timer!(timer_outer);
+
+        RowsOpening { idx, rows, values, proofs }
+    }
+
+
+    fn verify_rows_in_segment(&self, commitment: E::G1Affine, rows_in_segment: &Range<usize>, values: &[E::Fr], proof: E::G1Affine) -> bool {
+        if rows_in_segment.len() == self.L {
+            let poly_evals = Evaluations::from_vec_and_domain(values.to_vec(), self.domain_polycommit);
+            let poly_poly = poly_evals.interpolate();
+            return self.commitment_scheme.commit_polynomial(&poly_poly) == commitment;
+        }
+
+        // with Z the vanishing polynomial of the m opened points and I the interpolation of the
+        // values, check p(x) - I(x) = q(x) Z(x) at the secret as
+        // e(C - [I], h / beta^m) = e([q], Z(beta) h / beta^m), using only negative powers of h
+        let m = rows_in_segment.len();
+        let (poly_interpolation, points) = self.interpolate_rows(rows_in_segment, values);
+        let commitment_interpolation = self.commitment_scheme.commit_polynomial(&poly_interpolation);
+        let vanishing_h = vanishing_polynomial(&points).iter().enumerate()
+            .map(|(i, z)| self.commitment_scheme.neg_powers_of_h[m - i].mul(*z))
+            .fold(E::G2Projective::zero(), |acc, x| acc + x);
+
+        let lhs = E::pairing(commitment.into_projective() - commitment_interpolation.into_projective(), self.commitment_scheme.neg_powers_of_h[m]);
+        let rhs = E::pairing(proof, vanishing_h);
+        lhs == rhs
+    }
+
+
+    /// Check openings of the same rows from several nodes against the column commitments.
+    /// Requires the setup `setup_for_partial_retrieval`.
+    pub fn retrieve_verify_rows(&self, column_commitments: &[E::G1Affine], openings: &[RowsOpening<E>]) -> bool {
+        assert!(!self.commitment_scheme.neg_powers_of_h.is_empty(), "Partial retrieval needs setup_for_partial_retrieval");
+        if column_commitments.len() != self.get_num_column_commitments() {
+            return false;
+        }
+
+        let timer_all = start_timer!(|| "Verifying opened rows");
+
+        let coded_chunk_commitments_affine = self.encode_commitments_all(column_commitments);
+
+        for opening in openings {
+            if opening.idx >= self.n || opening.rows.start >= opening.rows.end || opening.rows.end > self.get_num_rows() || opening.values.len() != opening.rows.len() || opening.rows != openings[0].rows {
+                return false;
+            }
+
+            let segments = self.split_rows_into_segments(&opening.rows);
+            if opening.proofs.len() != segments.len() {
+                return false;
+            }
+
+            for ((s, rows_in_segment, offset), proof) in segments.into_iter().zip(opening.proofs.iter()) {
+                let timer_inner = start_timer!(|| format!("Node {} segment {}", opening.idx, s));
+                let values_in_segment = &opening.values[offset..offset + rows_in_segment.len()];
+                if !self.verify_rows_in_segment(coded_chunk_commitments_affine[s][opening.idx], &rows_in_segment, values_in_segment, *proof) {
+                    return false;
+                }
+                end_timer!(timer_inner);
+            }
+        }
+
+        end_timer!(timer_all);
+        true
+    }
+
+
+    /// Decode the opened rows, using `decoder_aux` from `retrieve_prepare_decoding` for the
+    /// nodes of `openings` (in that order).
+    pub fn retrieve_decode_partial_rows(&self, openings: &[RowsOpening<E>], decoder_aux: &Matrix<E::Fr>) -> FileMatrix<E::Fr> {
+        assert!(openings.len() == self.k);
+        let num_rows = openings[0].rows.len();
+        let data_coded_downloaded = FileMatrix::from_columns(num_rows, self.k, openings.iter().map(|o| o.values.clone()).collect());
+
+        self.retrieve_decode_rows(&data_coded_downloaded, decoder_aux)
+    }
+}
@@ -0,0 +1,223 @@
+use ark_ec::{AffineCurve, ProjectiveCurve, msm::{VariableBaseMSM}};
+use ark_ff::fields::{Field, PrimeField};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::{CanonicalSerialize};
+use ark_std::{Zero, One, start_timer, end_timer};
+
+use blake2::{Blake2b, Digest};
+
+use rand::{Rng};
+
+use crate::{ColumnCommitmentScheme};
+
+
+/// Domain separator for the generators, which anyone can re-derive (no trusted setup).
+const GENERATORS_DOMAIN: &[u8] = b"semiavidpr-pedersen-generators";
+
+
+/// Curve point with unknown discrete logarithm, derived from `message` by try-and-increment.
+pub(crate) fn hash_to_curve<G: AffineCurve>(domain: &[u8], message: &[u8]) -> G {
+    for counter in 0u64.. {
+        let digest = Blake2b::new()
+            .chain(domain)
+            .chain(message)
+            .chain(counter.to_le_bytes())
+            .finalize();
+        if let Some(p) = G::from_random_bytes(&digest) {
+            let p = p.mul_by_cofactor();
+            if !p.is_zero() {
+                return p;
+            }
+        }
+    }
+    unreachable!()
+}
+
+
+/// Fiat-Shamir transcript of an inner-product argument.
+struct Transcript {
+    hasher: Blake2b,
+}
+
+impl Transcript {
+    fn new() -> Self {
+        Transcript { hasher: Blake2b::new().chain(b"semiavidpr-ipa") }
+    }
+
+    fn append<T: CanonicalSerialize>(&mut self, item: &T) {
+        let mut bytes = Vec::new();
+        item.serialize(&mut bytes).unwrap();
+        self.hasher.update(&bytes);
+    }
+
+    fn challenge<F: PrimeField>(&mut self) -> F {
+        let digest = self.hasher.clone().finalize();
+        self.hasher.update(digest);
+        F::from_le_bytes_mod_order(&digest)
+    }
+}
+
+
+/// Inner-product argument that the committed column `a` satisfies `<a, b> = value` for the
+/// Lagrange coefficients `b` of the evaluation point, with one pair `(l, r)` of group elements
+/// per halving of the column, and the fully folded column `a`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IpaProof<G: AffineCurve> {
+    pub l: Vec<G>,
+    pub r: Vec<G>,
+    pub a: G::ScalarField,
+}
+
+
+/// Pedersen vector commitments to columns (in evaluation form) over the group of `G`, with
+/// inner-product-argument openings. The generators are hashed to the curve, so there is no
+/// trusted setup, at the price of proofs of `O(log L)` size and verification in `O(L)`.
+pub struct Pedersen<G: AffineCurve> {
+    domain: GeneralEvaluationDomain<G::ScalarField>,
+    generators: Vec<G>,
+    generator_u: G,
+}
+
+
+fn inner_product<F: Field>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b.iter()).map(|(x, y)| *x * y).sum()
+}
+
+fn msm<G: AffineCurve>(bases: &[G], scalars: &[G::ScalarField]) -> G::Projective {
+    let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+    VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+}
+
+
+impl<G: AffineCurve> Pedersen<G> {
+    /// Generator binding the claimed value, re-randomized per opening.
+    fn generator_value(&self, transcript: &mut Transcript, commitment: &G, point: G::ScalarField, value: G::ScalarField) -> G {
+        transcript.append(commitment);
+        transcript.append(&point);
+        transcript.append(&value);
+        self.generator_u.mul(transcript.challenge::<G::ScalarField>().into_repr()).into_affine()
+    }
+}
+
+
+impl<G: AffineCurve> ColumnCommitmentScheme<G::ScalarField> for Pedersen<G> {
+    type Commitment = G;
+    type Proof = IpaProof<G>;
+
+    fn setup<R: Rng + ?Sized>(_rng: &mut R, domain: GeneralEvaluationDomain<G::ScalarField>) -> Self {
+        let timer = start_timer!(|| "Deriving Pedersen generators");
+        let generators = (0..domain.size() as u64).map(|i| hash_to_curve(GENERATORS_DOMAIN, &i.to_le_bytes())).collect();
+        let generator_u = hash_to_curve(GENERATORS_DOMAIN, &(domain.size() as u64).to_le_bytes());
+        end_timer!(timer);
+
+        Pedersen { domain, generators, generator_u }
+    }
+
+    fn commit(&self, evals: &[G::ScalarField]) -> G {
+        assert!(evals.len() == self.generators.len());
+
+        let timer = start_timer!(|| "Pedersen commitment");
+        let commitment = msm(&self.generators, evals).into_affine();
+        end_timer!(timer);
+
+        commitment
+    }
+
+    fn combine(&self, commitments: &[G], coeffs: &[G::ScalarField]) -> G {
+        assert!(commitments.len() == coeffs.len());
+        msm(commitments, coeffs).into_affine()
+    }
+
+    fn encode(&self, commitments: &[G], domain: GeneralEvaluationDomain<G::ScalarField>) -> Vec<G> {
+        // the coded chunk commitments are the FFT of the column commitments
+        let mut commitments_projective: Vec<G::Projective> = commitments.iter().map(|h| h.into_projective()).collect();
+        commitments_projective.resize(domain.size(), G::Projective::zero());
+        domain.fft_in_place(&mut commitments_projective);
+        G::Projective::batch_normalization_into_affine(&commitments_projective)
+    }
+
+    fn open(&self, evals: &[G::ScalarField], point: G::ScalarField) -> (G::ScalarField, IpaProof<G>) {
+        assert!(evals.len() == self.generators.len());
+
+        let timer = start_timer!(|| "Inner-product argument");
+        let mut a = evals.to_vec();
+        let mut b = self.domain.evaluate_all_lagrange_coefficients(point);
+        let mut generators = self.generators.clone();
+        let value = inner_product(&a, &b);
+
+        let mut transcript = Transcript::new();
+        let commitment = msm(&generators, &a).into_affine();
+        let generator_u = self.generator_value(&mut transcript, &commitment, point, value);
+
+        let (mut l, mut r) = (Vec::new(), Vec::new());
+        while a.len() > 1 {
+            let half = a.len() / 2;
+            let (a_lo, a_hi) = a.split_at(half);
+            let (b_lo, b_hi) = b.split_at(half);
+            let (g_lo, g_hi) = generators.split_at(half);
+
+            let l_i = (msm(g_hi, a_lo) + generator_u.mul(inner_product(a_lo, b_hi).into_repr())).into_affine();
+            let r_i = (msm(g_lo, a_hi) + generator_u.mul(inner_product(a_hi, b_lo).into_repr())).into_affine();
+            transcript.append(&l_i);
+            transcript.append(&r_i);
+            let u: G::ScalarField = transcript.challenge();
+            let u_inv = u.inverse().unwrap();
+
+            let a_next = (0..half).map(|i| a_lo[i] * u + a_hi[i] * u_inv).collect();
+            let b_next = (0..half).map(|i| b_lo[i] * u_inv + b_hi[i] * u).collect();
+            let g_next = (0..half).map(|i| g_lo[i].mul(u_inv.into_repr()) + g_hi[i].mul(u.into_repr())).collect::<Vec<_>>();
+            a = a_next;
+            b = b_next;
+            generators = G::Projective::batch_normalization_into_affine(&g_next);
+            l.push(l_i);
+            r.push(r_i);
+        }
+        end_timer!(timer);
+
+        (value, IpaProof { l, r, a: a[0] })
+    }
+
+    fn verify(&self, commitment: &G, point: G::ScalarField, value: G::ScalarField, proof: &IpaProof<G>) -> bool {
+        let num_rounds = self.generators.len().trailing_zeros() as usize;
+        if proof.l.len() != num_rounds || proof.r.len() != num_rounds {
+            return false;
+        }
+
+        let timer = start_timer!(|| "Checking inner-product argument");
+        let mut transcript = Transcript::new();
+        let generator_u = self.generator_value(&mut transcript, commitment, point, value);
+
+        let mut folded = commitment.into_projective() + generator_u.mul(value.into_repr());
+        let mut challenges = Vec::with_capacity(num_rounds);
+        for (l_i, r_i) in proof.l.iter().zip(proof.r.iter()) {
+            transcript.append(l_i);
+            transcript.append(r_i);
+            let u: G::ScalarField = transcript.challenge();
+            let u_inv = u.inverse().unwrap();
+            folded += l_i.mul((u * u).into_repr()) + r_i.mul((u_inv * u_inv).into_repr());
+            challenges.push((u, u_inv));
+        }
+
+        // the folded generator and Lagrange coefficient are <s, generators> and <s, b>, where
+        // s_j multiplies u or u^-1 of every round depending on the half j fell into
+        let mut s = vec![G::ScalarField::one(); self.generators.len()];
+        for (round, (u, u_inv)) in challenges.iter().enumerate() {
+            let bit = 1 << (num_rounds - 1 - round);
+            for (j, s_j) in s.iter_mut().enumerate() {
+                *s_j *= if j & bit == 0 { u_inv } else { u };
+            }
+        }
+        let generator_folded = msm(&self.generators, &s);
+        let b_folded = inner_product(&s, &self.domain.evaluate_all_lagrange_coefficients(point));
+
+        let expected = generator_folded.into_affine().mul(proof.a.into_repr()) + generator_u.mul((proof.a * b_folded).into_repr());
+        let ret_val = folded == expected;
+        end_timer!(timer);
+
+        ret_val
+    }
+
+    fn proof_size(proof: &IpaProof<G>) -> usize {
+        proof.l.iter().chain(proof.r.iter()).map(|p| p.serialized_size()).sum::<usize>() + proof.a.serialized_size()
+    }
+}
@@ -0,0 +1,104 @@
+use ark_ec::{PairingEngine};
+use ark_poly::{EvaluationDomain};
+use ark_std::{One, start_timer, end_timer};
+
+use std::collections::{HashSet};
+
+use crate::{SemiAvidPr, ColumnCommitmentScheme, FileMatrix, Matrix};
+
+
+/// What to do with chunks downloaded beyond the `k` needed for decoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RedundantRetrievalMode {
+    /// Decode from the cheapest `k` chunks and ignore the others.
+    PickSubset,
+    /// Decode from `k` chunks and check that re-encoding yields the others.
+    CrossCheck,
+}
+
+/// Which chunks a retrieval from more than `k` chunks used, and which check it performed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RedundantRetrievalReport {
+    /// Decoded from `idxs_used`, reusing a cached decoding matrix if `decoder_cached`.
+    SubsetPicked { idxs_used: Vec<usize>, decoder_cached: bool },
+    /// Decoded from `idxs_used`, and the chunks of `idxs_checked` agree with the decoded file.
+    CrossChecked { idxs_used: Vec<usize>, idxs_checked: Vec<usize> },
+}
+
+/// The downloaded chunks are not all consistent with a single file: decoding from `idxs_used`
+/// does not reproduce the chunks of `idxs_inconsistent`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InconsistentChunks {
+    pub idxs_used: Vec<usize>,
+    pub idxs_inconsistent: Vec<usize>,
+}
+
+impl core::fmt::Display for InconsistentChunks {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "chunks {:?} are inconsistent with decoding from chunks {:?}", self.idxs_inconsistent, self.idxs_used)
+    }
+}
+
+impl std::error::Error for InconsistentChunks {}
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
+    /// Positions (into `idxs_download_nodes`) of the `k` chunks to decode from: a subset whose
+    /// decoding matrix is cached if there is one, otherwise the first `k` chunks downloaded.
+    fn pick_decoding_subset(&self, idxs_download_nodes: &[usize]) -> (Vec<usize>, bool) {
+        let idxs_set: HashSet<usize> = idxs_download_nodes.iter().copied().collect();
+        let key_cached = self.decoder_cache.lock().unwrap().find_key_within(&idxs_set);
+        match key_cached {
+            Some(key) => (key.iter().map(|i| idxs_download_nodes.iter().position(|j| j == i).unwrap()).collect(), true),
+            None => ((0..self.k).collect(), false),
+        }
+    }
+
+
+    /// Decode from any number `>= k` of downloaded chunks, where column `c` of
+    /// `data_coded_downloaded` is the chunk of node `idxs_download_nodes[c]`.
+    pub fn retrieve_decode_rows_redundant(&self, data_coded_downloaded: &FileMatrix<E::Fr>, idxs_download_nodes: &[usize], mode: RedundantRetrievalMode) -> Result<(FileMatrix<E::Fr>, RedundantRetrievalReport), InconsistentChunks> {
+        assert!(idxs_download_nodes.len() >= self.k);
+        assert!(data_coded_downloaded.width() == idxs_download_nodes.len());
+        assert!(idxs_download_nodes.iter().collect::<HashSet<_>>().len() == idxs_download_nodes.len());
+
+        let (positions_used, decoder_cached) = self.pick_decoding_subset(idxs_download_nodes);
+        let idxs_used: Vec<usize> = positions_used.iter().map(|&p| idxs_download_nodes[p]).collect();
+
+        let decoder_aux = self.retrieve_prepare_decoding(&idxs_used);
+        let data_decoded = self.retrieve_decode_rows(&data_coded_downloaded.pick_columns(&positions_used), &decoder_aux);
+
+        match mode {
+            RedundantRetrievalMode::PickSubset => Ok((data_decoded, RedundantRetrievalReport::SubsetPicked { idxs_used, decoder_cached })),
+            RedundantRetrievalMode::CrossCheck => {
+                let positions_extra: Vec<usize> = (0..idxs_download_nodes.len()).filter(|p| !positions_used.contains(p)).collect();
+                let idxs_checked: Vec<usize> = positions_extra.iter().map(|&p| idxs_download_nodes[p]).collect();
+
+                // only the extra chunks are re-encoded, by the corresponding columns of the encoding matrix
+                let timer = start_timer!(|| "Re-encoding extra chunks");
+                let mut encoding = Matrix::zeros(self.k, idxs_checked.len());
+                for (col, &idx) in idxs_checked.iter().enumerate() {
+                    let point = self.domain_encoding.element(idx);
+                    let mut power = E::Fr::one();
+                    for row in 0..self.k {
+                        encoding[(row, col)] = power;
+                        power *= point;
+                    }
+                }
+                let data_reencoded = data_decoded.mul(&encoding);
+                end_timer!(timer);
+
+                let idxs_inconsistent: Vec<usize> = positions_extra.iter().enumerate()
+                    .filter(|(col, &p)| data_reencoded.column(*col) != data_coded_downloaded.column(p))
+                    .map(|(_, &p)| idxs_download_nodes[p])
+                    .collect();
+
+                if idxs_inconsistent.is_empty() {
+                    Ok((data_decoded, RedundantRetrievalReport::CrossChecked { idxs_used, idxs_checked }))
+                } else {
+                    Err(InconsistentChunks { idxs_used, idxs_inconsistent })
+                }
+            },
+        }
+    }
+}
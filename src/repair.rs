@@ -0,0 +1,42 @@
+use ark_ff::fields::{Field};
+use ark_ec::{PairingEngine};
+use ark_poly::{EvaluationDomain};
+use ark_std::{start_timer, end_timer};
+
+use crate::{SemiAvidPr, ColumnCommitmentScheme, FileMatrix, Matrix};
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
+    /// Coefficients with which the chunks of `idxs_download_nodes` combine to the chunk of node
+    /// `idx_target`, i.e., the decoding matrix applied to the encoding vector of `idx_target`.
+    pub fn repair_prepare(&self, idxs_download_nodes: &[usize], idx_target: usize) -> Matrix<E::Fr> {
+        assert!(idx_target < self.n);
+
+        let point = self.domain_encoding.element(idx_target);
+        let encoding = Matrix::from_nested_vec(self.k, 1, (0..self.k).map(|i| vec![point.pow([i as u64])]).collect());
+
+        self.retrieve_prepare_decoding(idxs_download_nodes).mul(&encoding)
+    }
+
+
+    /// Rebuild the chunk of a node from `k` (verified) downloaded chunks, without decoding the
+    /// file: the target's coded column is a linear combination of the downloaded columns.
+    pub fn repair_compute_chunk(&self, data_coded_downloaded: &FileMatrix<E::Fr>, idxs_download_nodes: &[usize], idx_target: usize) -> Vec<E::Fr> {
+        assert!(data_coded_downloaded.width() == idxs_download_nodes.len());
+
+        let repair_aux = self.repair_prepare(idxs_download_nodes, idx_target);
+
+        let timer = start_timer!(|| "Combining downloaded chunks");
+        let chunk = data_coded_downloaded.mul(&repair_aux).column(0).to_vec();
+        end_timer!(timer);
+
+        chunk
+    }
+
+
+    /// Check a (repaired) chunk of node `idx` against its coded chunk commitments before the
+    /// node accepts it, like the node checks its chunk in the dispersal.
+    pub fn repair_verify_chunk(&self, column_commitments: &[C::Commitment], chunk: &[E::Fr], idx: usize) -> bool {
+        self.disperse_verify_chunk(column_commitments, chunk, idx)
+    }
+}
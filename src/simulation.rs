@@ -0,0 +1,547 @@
+use ark_ec::{PairingEngine};
+use ark_serialize::{CanonicalSerialize};
+use ark_std::{Zero, One};
+
+use rand::{Rng};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Instant};
+
+use crate::{SemiAvidPr, ColumnCommitmentScheme, FileMatrix, AuditChallenge, AuditResponse, ChunkId, DispersalCertificate, NodeRegistry, Signature, SigningKey};
+
+
+/// Size of a message without payload (request), in bytes.
+const CONTROL_MESSAGE_BYTES: usize = std::mem::size_of::<usize>();
+
+
+/// Participant of the simulated protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Actor {
+    /// Disperses the file, and later retrieves it.
+    Client,
+    /// Storage node with index `idx`, storing chunk `idx`.
+    Node(usize),
+    /// Light client sampling entries of the stored chunks.
+    Sampler,
+    /// Second client, retrieving the file knowing only the column commitments and the
+    /// dispersal certificate from the client.
+    Reader,
+}
+
+
+/// Behaviour of the client in the dispersal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientBehavior {
+    Honest,
+    /// Sends the nodes `idxs` a chunk that does not match the column commitments.
+    InconsistentChunks(BTreeSet<usize>),
+}
+
+
+/// Behaviour of a storage node. Byzantine nodes acknowledge (and sign) whatever chunk they
+/// receive. All nodes sign the chunks they return.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeBehavior {
+    Honest,
+    /// Never answers retrieval or sampling requests.
+    Withhold,
+    /// Answers retrieval and sampling requests with corrupted chunks and values.
+    Corrupt,
+    /// Answers sampling requests and the first retrieval request honestly, but every later
+    /// retrieval request with a different chunk, so that each retrieving party on its own
+    /// sees a well-formed, signed answer.
+    Equivocate,
+}
+
+
+/// Misbehaviour detected by an honest party, attributed to the `Actor` it is reported with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Fault {
+    /// The client sent node `idx` a chunk that does not match the column commitments.
+    InconsistentChunk(usize),
+    /// The node returned a chunk that does not match the column commitments.
+    InvalidChunk,
+    /// The node failed a sample.
+    FailedSample,
+    /// The node acknowledged its chunk, but left a retrieval or sampling request unanswered.
+    Withheld,
+    /// The node signed different chunks for the same file, as the retrieving parties find
+    /// when they compare the signed chunks they received.
+    Equivocation,
+}
+
+
+/// Directed link: a message of `b` bytes arrives `latency_seconds + b / bandwidth_bytes_per_second`
+/// after it was sent, and messages on the same link are transmitted one after another.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinkConfig {
+    pub latency_seconds: f64,
+    pub bandwidth_bytes_per_second: f64,
+}
+
+impl LinkConfig {
+    pub fn new(latency_seconds: f64, bandwidth_bytes_per_second: f64) -> Self {
+        assert!(latency_seconds >= 0.0 && bandwidth_bytes_per_second > 0.0);
+        Self { latency_seconds, bandwidth_bytes_per_second }
+    }
+
+    fn latency_ns(&self) -> u64 {
+        (self.latency_seconds * 1e9).round() as u64
+    }
+
+    fn transmission_ns(&self, bytes: usize) -> u64 {
+        (bytes as f64 / self.bandwidth_bytes_per_second * 1e9).round() as u64
+    }
+}
+
+
+/// Parameters of a simulation run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimConfig {
+    pub default_link: LinkConfig,
+    /// Links that differ from `default_link`.
+    pub links: BTreeMap<(Actor, Actor), LinkConfig>,
+    /// Acknowledgements after which the client considers the file dispersed, aggregates their
+    /// signatures into a `DispersalCertificate`, and starts the retrieval and the sampling.
+    pub num_acks: usize,
+    /// Entries that the sampler opens, at random nodes and rows.
+    pub num_samples: usize,
+    /// Whether the reader retrieves the file, too.
+    pub with_reader: bool,
+    /// Whether computation occupies an actor and delays its messages by the measured CPU time.
+    /// Without, the schedule (though not the reported CPU times) is fully deterministic.
+    pub charge_cpu_time: bool,
+
+    pub client_behavior: ClientBehavior,
+    /// Nodes that are not `NodeBehavior::Honest`.
+    pub node_behaviors: BTreeMap<usize, NodeBehavior>,
+}
+
+impl SimConfig {
+    /// Acknowledgements from all `n` nodes, 8 samples, no reader, the same `default_link`
+    /// everywhere, computation that takes no simulated time, and only honest actors.
+    pub fn new(n: usize, default_link: LinkConfig) -> Self {
+        Self {
+            default_link, links: BTreeMap::new(), num_acks: n, num_samples: 8, with_reader: false, charge_cpu_time: false,
+            client_behavior: ClientBehavior::Honest, node_behaviors: BTreeMap::new(),
+        }
+    }
+
+    pub fn set_link(&mut self, from: Actor, to: Actor, link: LinkConfig) {
+        self.links.insert((from, to), link);
+    }
+
+    pub fn link(&self, from: Actor, to: Actor) -> LinkConfig {
+        *self.links.get(&(from, to)).unwrap_or(&self.default_link)
+    }
+
+    pub fn set_node_behavior(&mut self, idx: usize, behavior: NodeBehavior) {
+        self.node_behaviors.insert(idx, behavior);
+    }
+
+    pub fn node_behavior(&self, idx: usize) -> NodeBehavior {
+        *self.node_behaviors.get(&idx).unwrap_or(&NodeBehavior::Honest)
+    }
+}
+
+
+/// Outcome of a simulation run. Latencies are `None` for phases that did not complete.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimReport {
+    /// From the start until the client has `num_acks` acknowledgements.
+    pub dispersal_latency_seconds: Option<f64>,
+    /// From the end of the dispersal until the client has decoded the file.
+    pub retrieval_latency_seconds: Option<f64>,
+    /// From the end of the dispersal until the sampler has checked all samples.
+    pub sampling_latency_seconds: Option<f64>,
+
+    pub num_acks: usize,
+    /// Nodes whose chunks the client decoded from, in order of arrival.
+    pub idxs_retrieved_from: Vec<usize>,
+    pub retrieved_correctly: bool,
+    /// Whether the reader decoded the file.
+    pub reader_retrieved_correctly: bool,
+    pub num_samples_passed: usize,
+    pub num_samples_failed: usize,
+    /// Whether the sampler accepted the dispersal certificate of the client.
+    pub certificate_verified: bool,
+    pub faults: BTreeSet<(Actor, Fault)>,
+
+    pub bytes_per_link: BTreeMap<(Actor, Actor), usize>,
+    pub cpu_seconds: BTreeMap<Actor, f64>,
+}
+
+impl SimReport {
+    pub fn bytes_sent(&self, actor: Actor) -> usize {
+        self.bytes_per_link.iter().filter(|((from, _), _)| *from == actor).map(|(_, b)| b).sum()
+    }
+
+    pub fn bytes_received(&self, actor: Actor) -> usize {
+        self.bytes_per_link.iter().filter(|((_, to), _)| *to == actor).map(|(_, b)| b).sum()
+    }
+
+    pub fn faulty_actors(&self) -> BTreeSet<Actor> {
+        self.faults.iter().map(|(actor, _)| *actor).collect()
+    }
+}
+
+
+enum Message<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> {
+    Start,
+    Disperse { column_commitments: Vec<C::Commitment>, chunk: Vec<E::Fr> },
+    Ack { signature: Signature<E> },
+    Commitments { column_commitments: Vec<C::Commitment>, certificate: DispersalCertificate<E> },
+    RetrieveRequest,
+    RetrieveResponse { chunk: Vec<E::Fr>, signature: Signature<E> },
+    SampleRequest { id: usize, challenge: AuditChallenge<E::Fr> },
+    SampleResponse { id: usize, response: AuditResponse<E::Fr, C::Proof> },
+}
+
+
+type MessageQueue<E, C> = BTreeMap<(u64, u64), (Actor, Actor, Message<E, C>)>;
+/// Column commitments and chunk that a node acknowledged.
+type NodeChunk<E, C> = (Vec<<C as ColumnCommitmentScheme<<E as PairingEngine>::Fr>>::Commitment>, Vec<<E as PairingEngine>::Fr>);
+/// Valid chunks, with the indices of their nodes, in order of arrival.
+type RetrievedChunks<F> = Vec<(usize, Vec<F>)>;
+
+
+/// State of all actors, and the queue of messages in flight ordered by arrival time (in
+/// nanoseconds) and then by the order in which they were sent.
+struct Simulation<'s, E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> {
+    scheme: &'s SemiAvidPr<E, C>,
+    config: &'s SimConfig,
+
+    queue: MessageQueue<E, C>,
+    num_sent: u64,
+    now: u64,
+    handler_started: Instant,
+    busy_until: BTreeMap<Actor, u64>,
+    link_free_at: BTreeMap<(Actor, Actor), u64>,
+
+    file: FileMatrix<E::Fr>,
+    client_column_commitments: Vec<C::Commitment>,
+    client_signatures: Vec<(usize, Signature<E>)>,
+    dispersed_at: Option<u64>,
+    node_chunks: Vec<Option<NodeChunk<E, C>>>,
+    node_signing_keys: Vec<SigningKey<E>>,
+    /// Retrieval requests per node that it answered.
+    node_num_retrievals: Vec<usize>,
+    registry: NodeRegistry<E>,
+    reader_column_commitments: Vec<C::Commitment>,
+    /// Per retrieving party.
+    retrieved_chunks: BTreeMap<Actor, RetrievedChunks<E::Fr>>,
+    /// Different signed chunks per node, as received by the retrieving parties.
+    signed_chunk_ids: BTreeMap<usize, BTreeMap<ChunkId, Signature<E>>>,
+    sampler_column_commitments: Vec<C::Commitment>,
+    sampler_challenges: Vec<(usize, AuditChallenge<E::Fr>)>,
+    /// Retrieval and sampling requests per node that it has not answered (yet).
+    num_requests_pending: BTreeMap<usize, usize>,
+
+    report: SimReport,
+}
+
+
+impl<'s, E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> Simulation<'s, E, C> {
+    fn new(scheme: &'s SemiAvidPr<E, C>, config: &'s SimConfig, file: FileMatrix<E::Fr>, node_signing_keys: Vec<SigningKey<E>>, registry: NodeRegistry<E>) -> Self {
+        Self {
+            scheme, config,
+            queue: BTreeMap::new(), num_sent: 0, now: 0, handler_started: Instant::now(),
+            busy_until: BTreeMap::new(), link_free_at: BTreeMap::new(),
+            file, client_column_commitments: Vec::new(), client_signatures: Vec::new(), dispersed_at: None,
+            node_chunks: vec![None; scheme.n], node_signing_keys, node_num_retrievals: vec![0; scheme.n], registry,
+            reader_column_commitments: Vec::new(), retrieved_chunks: BTreeMap::new(), signed_chunk_ids: BTreeMap::new(),
+            sampler_column_commitments: Vec::new(), sampler_challenges: Vec::new(),
+            num_requests_pending: BTreeMap::new(),
+            report: SimReport::default(),
+        }
+    }
+
+    /// Simulated time in the current handler, including its computation so far if charged.
+    fn clock(&self) -> u64 {
+        if self.config.charge_cpu_time {
+            self.now + self.handler_started.elapsed().as_nanos() as u64
+        } else {
+            self.now
+        }
+    }
+
+    fn seconds_since(&self, start: u64) -> f64 {
+        (self.clock() - start) as f64 * 1e-9
+    }
+
+    fn message_size(&self, message: &Message<E, C>) -> usize {
+        let size_entry = E::Fr::zero().serialized_size();
+        let size_commitments = |c: &[C::Commitment]| c.iter().map(|c| c.serialized_size()).sum::<usize>();
+        match message {
+            Message::Start => 0,
+            Message::Disperse { column_commitments, chunk } => size_commitments(column_commitments) + chunk.len() * size_entry,
+            Message::Ack { signature } => signature.serialized_size(),
+            Message::RetrieveRequest => CONTROL_MESSAGE_BYTES,
+            Message::Commitments { column_commitments, certificate } => size_commitments(column_commitments) + certificate.serialized_size(),
+            Message::RetrieveResponse { chunk, signature } => chunk.len() * size_entry + signature.serialized_size(),
+            Message::SampleRequest { challenge: AuditChallenge::Rows(rows), .. } => CONTROL_MESSAGE_BYTES * (1 + rows.len()),
+            Message::SampleRequest { challenge: AuditChallenge::Point(_), .. } => CONTROL_MESSAGE_BYTES + size_entry,
+            Message::SampleResponse { response, .. } => CONTROL_MESSAGE_BYTES + response.values.len() * size_entry + response.proofs.iter().map(C::proof_size).sum::<usize>(),
+        }
+    }
+
+    fn send(&mut self, departure: u64, from: Actor, to: Actor, message: Message<E, C>) {
+        match (to, &message) {
+            (Actor::Node(idx), Message::RetrieveRequest | Message::SampleRequest { .. }) => *self.num_requests_pending.entry(idx).or_insert(0) += 1,
+            (_, Message::RetrieveResponse { .. } | Message::SampleResponse { .. }) => if let Actor::Node(idx) = from { *self.num_requests_pending.get_mut(&idx).unwrap() -= 1 },
+            _ => (),
+        }
+
+        let link = self.config.link(from, to);
+        let bytes = self.message_size(&message);
+        *self.report.bytes_per_link.entry((from, to)).or_insert(0) += bytes;
+
+        let link_free_at = self.link_free_at.entry((from, to)).or_insert(0);
+        let transmitted = std::cmp::max(departure, *link_free_at) + link.transmission_ns(bytes);
+        *link_free_at = transmitted;
+
+        self.queue.insert((transmitted + link.latency_ns(), self.num_sent), (from, to, message));
+        self.num_sent += 1;
+    }
+
+
+    fn run<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.queue.insert((0, 0), (Actor::Client, Actor::Client, Message::Start));
+        self.num_sent = 1;
+
+        while let Some(((arrival, _), (from, to, message))) = self.queue.pop_first() {
+            self.now = if self.config.charge_cpu_time {
+                std::cmp::max(arrival, *self.busy_until.get(&to).unwrap_or(&0))
+            } else {
+                arrival
+            };
+
+            self.handler_started = Instant::now();
+            let outgoing = self.handle(rng, from, to, message);
+            *self.report.cpu_seconds.entry(to).or_insert(0.0) += self.handler_started.elapsed().as_secs_f64();
+
+            let done = self.clock();
+            self.busy_until.insert(to, done);
+            for (dest, message) in outgoing {
+                self.send(done, to, dest, message);
+            }
+        }
+
+        for (&idx, &num_pending) in self.num_requests_pending.iter() {
+            if num_pending > 0 {
+                self.report.faults.insert((Actor::Node(idx), Fault::Withheld));
+            }
+        }
+        for (&idx, chunk_ids) in self.signed_chunk_ids.iter() {
+            if chunk_ids.len() > 1 {
+                self.report.faults.insert((Actor::Node(idx), Fault::Equivocation));
+            }
+        }
+    }
+
+
+    /// Same chunk (or sampled values) with the first entry changed.
+    fn corrupt(entries: &mut [E::Fr]) {
+        if let Some(entry) = entries.first_mut() {
+            *entry += E::Fr::one();
+        }
+    }
+
+
+    fn handle<R: Rng + ?Sized>(&mut self, rng: &mut R, from: Actor, to: Actor, message: Message<E, C>) -> Vec<(Actor, Message<E, C>)> {
+        let scheme = self.scheme;
+        match (to, message) {
+            (Actor::Client, Message::Start) => {
+                self.client_column_commitments = scheme.disperse_compute_column_commitments(&self.file);
+                let data_coded = scheme.disperse_encode_rows(&self.file);
+                (0..scheme.n).map(|i| {
+                    let mut chunk = data_coded.column(i).to_vec();
+                    if let ClientBehavior::InconsistentChunks(idxs) = &self.config.client_behavior {
+                        if idxs.contains(&i) {
+                            Self::corrupt(&mut chunk);
+                        }
+                    }
+                    (Actor::Node(i), Message::Disperse { column_commitments: self.client_column_commitments.clone(), chunk })
+                }).collect()
+            },
+            (Actor::Node(idx), Message::Disperse { column_commitments, chunk }) => {
+                let signing_key = &self.node_signing_keys[idx];
+                let signature = if self.config.node_behavior(idx) == NodeBehavior::Honest {
+                    match scheme.disperse_sign_chunk(signing_key, &column_commitments, &chunk, idx) {
+                        Some(signature) => signature,
+                        None => {
+                            self.report.faults.insert((Actor::Client, Fault::InconsistentChunk(idx)));
+                            return vec![];
+                        },
+                    }
+                } else {
+                    signing_key.sign(&scheme.file_id(&column_commitments))
+                };
+                self.node_chunks[idx] = Some((column_commitments, chunk));
+                vec![(Actor::Client, Message::Ack { signature })]
+            },
+            (Actor::Client, Message::Ack { signature }) => {
+                if let Actor::Node(idx) = from {
+                    self.client_signatures.push((idx, signature));
+                }
+                self.report.num_acks += 1;
+                if self.report.num_acks != self.config.num_acks {
+                    return vec![];
+                }
+
+                let file_id = scheme.file_id(&self.client_column_commitments);
+                let certificate = match DispersalCertificate::aggregate(&self.registry, &file_id, &self.client_signatures, self.config.num_acks) {
+                    Some(certificate) => certificate,
+                    None => return vec![],
+                };
+
+                let now = self.clock();
+                self.dispersed_at = Some(now);
+                self.report.dispersal_latency_seconds = Some(now as f64 * 1e-9);
+                // retrieve from, and sample, the nodes that acknowledged
+                let idxs_acked = certificate.signers();
+                let readers = if self.config.with_reader { vec![Actor::Sampler, Actor::Reader] } else { vec![Actor::Sampler] };
+                readers.into_iter().map(|actor| (actor, Message::Commitments { column_commitments: self.client_column_commitments.clone(), certificate: certificate.clone() }))
+                    .chain(idxs_acked.into_iter().map(|i| (Actor::Node(i), Message::RetrieveRequest)))
+                    .collect()
+            },
+            (Actor::Node(idx), Message::RetrieveRequest) => {
+                let behavior = self.config.node_behavior(idx);
+                match &self.node_chunks[idx] {
+                    Some(_) if behavior == NodeBehavior::Withhold => vec![],
+                    Some((column_commitments, chunk)) => {
+                        let mut chunk = chunk.clone();
+                        match behavior {
+                            NodeBehavior::Corrupt => Self::corrupt(&mut chunk),
+                            // a different chunk for every request after the first
+                            NodeBehavior::Equivocate if self.node_num_retrievals[idx] > 0 => chunk[0] += E::Fr::from(self.node_num_retrievals[idx] as u64),
+                            _ => (),
+                        }
+                        self.node_num_retrievals[idx] += 1;
+                        let chunk_id = scheme.chunk_id(&scheme.file_id(column_commitments), &chunk, idx);
+                        let signature = self.node_signing_keys[idx].sign_chunk(&chunk_id);
+                        vec![(from, Message::RetrieveResponse { chunk, signature })]
+                    },
+                    None => vec![],
+                }
+            },
+            (Actor::Client | Actor::Reader, Message::RetrieveResponse { chunk, signature }) => {
+                let idx = match from { Actor::Node(idx) => idx, _ => return vec![] };
+                let column_commitments = if to == Actor::Client { &self.client_column_commitments } else { &self.reader_column_commitments };
+                // unsigned chunks are not attributable to the node, and dropped
+                let chunk_id = scheme.chunk_id(&scheme.file_id(column_commitments), &chunk, idx);
+                if !self.registry.public_key(idx).verify_chunk(&chunk_id, &signature) {
+                    return vec![];
+                }
+                self.signed_chunk_ids.entry(idx).or_default().insert(chunk_id, signature);
+
+                // every chunk is checked, also after decoding, to report all invalid ones
+                if !scheme.disperse_verify_chunk(column_commitments, &chunk, idx) {
+                    self.report.faults.insert((from, Fault::InvalidChunk));
+                    return vec![];
+                }
+                let chunks = self.retrieved_chunks.entry(to).or_default();
+                if chunks.len() == scheme.k {
+                    return vec![];
+                }
+
+                chunks.push((idx, chunk));
+                if chunks.len() == scheme.k {
+                    let idxs_download_nodes: Vec<usize> = chunks.iter().map(|(i, _)| *i).collect();
+                    let columns = chunks.iter().map(|(_, c)| c.clone()).collect();
+                    let data_coded_downloaded = FileMatrix::from_columns(scheme.get_num_rows(), scheme.k, columns);
+                    let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+                    let data_decoded = scheme.retrieve_decode_rows(&data_coded_downloaded, &decoder_aux);
+
+                    if to == Actor::Reader {
+                        self.report.reader_retrieved_correctly = data_decoded == self.file;
+                        return vec![];
+                    }
+                    self.report.retrieved_correctly = data_decoded == self.file;
+                    self.report.idxs_retrieved_from = idxs_download_nodes;
+                    self.report.retrieval_latency_seconds = Some(self.seconds_since(self.dispersed_at.unwrap()));
+                }
+                vec![]
+            },
+            (Actor::Reader, Message::Commitments { column_commitments, certificate }) => {
+                if !certificate.verify(&self.registry, &scheme.file_id(&column_commitments), self.config.num_acks) {
+                    return vec![];
+                }
+                self.reader_column_commitments = column_commitments;
+                certificate.signers().into_iter().map(|i| (Actor::Node(i), Message::RetrieveRequest)).collect()
+            },
+            (Actor::Sampler, Message::Commitments { column_commitments, certificate }) => {
+                self.report.certificate_verified = certificate.verify(&self.registry, &scheme.file_id(&column_commitments), self.config.num_acks);
+                if !self.report.certificate_verified {
+                    return vec![];
+                }
+                let idxs_acked = certificate.signers();
+                self.sampler_column_commitments = column_commitments;
+                self.sampler_challenges = (0..self.config.num_samples).map(|_| {
+                    (idxs_acked[rng.gen_range(0..idxs_acked.len())], AuditChallenge::Rows(vec![rng.gen_range(0..scheme.get_num_rows())]))
+                }).collect();
+                if self.sampler_challenges.is_empty() {
+                    self.report.sampling_latency_seconds = Some(0.0);
+                }
+                self.sampler_challenges.iter().enumerate().map(|(id, (idx, challenge))| {
+                    (Actor::Node(*idx), Message::SampleRequest { id, challenge: challenge.clone() })
+                }).collect()
+            },
+            (Actor::Node(idx), Message::SampleRequest { id, challenge }) => {
+                let behavior = self.config.node_behavior(idx);
+                match &self.node_chunks[idx] {
+                    Some(_) if behavior == NodeBehavior::Withhold => vec![],
+                    Some((_, chunk)) => {
+                        let mut response = match scheme.audit_respond(chunk, &challenge) {
+                            Ok(response) => response,
+                            Err(_) => return vec![],
+                        };
+                        if behavior == NodeBehavior::Corrupt {
+                            Self::corrupt(&mut response.values);
+                        }
+                        vec![(from, Message::SampleResponse { id, response })]
+                    },
+                    None => vec![],
+                }
+            },
+            (Actor::Sampler, Message::SampleResponse { id, response }) => {
+                let (idx, challenge) = &self.sampler_challenges[id];
+                if scheme.audit_verify(&self.sampler_column_commitments, *idx, challenge, &response) {
+                    self.report.num_samples_passed += 1;
+                } else {
+                    self.report.num_samples_failed += 1;
+                    self.report.faults.insert((Actor::Node(*idx), Fault::FailedSample));
+                }
+                if self.report.num_samples_passed + self.report.num_samples_failed == self.sampler_challenges.len() {
+                    self.report.sampling_latency_seconds = Some(self.seconds_since(self.dispersed_at.unwrap()));
+                }
+                vec![]
+            },
+            _ => vec![],
+        }
+    }
+}
+
+
+impl<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>> SemiAvidPr<E, C> {
+    /// Run disperse, acknowledge, retrieve and sample for a random file over a simulated network
+    /// of a client, the `n` storage nodes, a sampler and optionally a reader, see [`SimConfig`].
+    /// The client and the reader retrieve from, and the sampler samples, the nodes that
+    /// acknowledged by the end of the dispersal, i.e., the signers of the dispersal certificate.
+    /// The nodes register fresh keys first.
+    pub fn simulate_network<R: Rng + ?Sized>(&self, rng: &mut R, config: &SimConfig) -> SimReport {
+        assert!(config.num_acks >= 1 && config.num_acks <= self.n);
+        assert!(config.node_behaviors.keys().all(|&idx| idx < self.n));
+
+        let file = self.generate_random_file(rng);
+        let node_signing_keys: Vec<SigningKey<E>> = (0..self.n).map(|_| SigningKey::generate(rng)).collect();
+        let mut registry = NodeRegistry::new();
+        for signing_key in node_signing_keys.iter() {
+            registry.register(signing_key.public_key(), &signing_key.prove_possession()).unwrap();
+        }
+
+        let mut simulation = Simulation::new(self, config, file, node_signing_keys, registry);
+        simulation.run(rng);
+        simulation.report
+    }
+}
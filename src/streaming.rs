@@ -0,0 +1,72 @@
+use ark_ec::{ProjectiveCurve, AffineCurve, PairingEngine};
+use ark_std::{Zero, start_timer, end_timer};
+
+use crate::{SemiAvidPr};
+
+
+/// Incremental dispersal of a file whose rows become available one after another.
+///
+/// Column commitments are linear in the data when expressed in the Lagrange basis of
+/// `domain_polycommit`, so every pushed row is folded into the running column commitments
+/// with one scalar multiplication per column, and its encoding is returned right away.
+/// Rows that are never pushed count as zero rows, i.e., finalizing early yields the
+/// commitments of the zero-padded file.
+pub struct DispersalStream<'s, E: PairingEngine> {
+    scheme: &'s SemiAvidPr<E>,
+    column_commitments: Vec<E::G1Projective>,
+    num_rows_pushed: usize,
+}
+
+
+impl<'s, E: PairingEngine> DispersalStream<'s, E> {
+    pub(crate) fn new(scheme: &'s SemiAvidPr<E>) -> Self {
+        Self {
+            scheme,
+            column_commitments: vec![E::G1Projective::zero(); scheme.get_num_column_commitments()],
+            num_rows_pushed: 0,
+        }
+    }
+
+    pub fn get_num_rows_pushed(&self) -> usize {
+        self.num_rows_pushed
+    }
+
+    /// Append the next row of the file, returning its coded row (one entry per storage node).
+    pub fn push_row(&mut self, row: &[E::Fr]) -> Vec<E::Fr> {
+        let scheme = self.scheme;
+        assert!(row.len() == scheme.k);
+        assert!(self.num_rows_pushed < scheme.get_num_rows());
+
+        let (segment, row_in_segment) = (self.num_rows_pushed / scheme.L, self.num_rows_pushed % scheme.L);
+
+        let timer = start_timer!(|| format!("Updating column commitments with row {}", self.num_rows_pushed));
+        let lagrange_g = scheme.commitment_scheme.ck_lagrange[row_in_segment];
+        for (commitment, value) in self.column_commitments[segment*scheme.k..(segment+1)*scheme.k].iter_mut().zip(row.iter()) {
+            if !value.is_zero() {
+                *commitment += lagrange_g.mul(*value);
+            }
+        }
+        end_timer!(timer);
+
+        let timer = start_timer!(|| format!("Encoding row {}", self.num_rows_pushed));
+        let row_coded = scheme.encode_row(row);
+        end_timer!(timer);
+
+        self.num_rows_pushed += 1;
+        row_coded
+    }
+
+    /// Column commitments of all rows pushed so far, identical to what
+    /// `disperse_compute_column_commitments` returns for the (zero-padded) file.
+    pub fn finalize(self) -> Vec<E::G1Affine> {
+        E::G1Projective::batch_normalization_into_affine(&self.column_commitments)
+    }
+}
+
+
+impl<E: PairingEngine> SemiAvidPr<E> {
+    /// Start a dispersal to which rows are fed one at a time, see [`DispersalStream`].
+    pub fn disperse_stream(&self) -> DispersalStream<'_, E> {
+        DispersalStream::new(self)
+    }
+}
@@ -1,7 +1,19 @@
 use super::*;
 
+use ark_ec::{ProjectiveCurve, AffineCurve};
+use ark_poly::{evaluations::univariate::{Evaluations}};
+use ark_poly_commit::kzg10::{KZG10};
+use ark_serialize::{CanonicalSerialize, CanonicalDeserialize};
+use ark_std::{Zero, One};
+
+use rand::{SeedableRng, rngs::StdRng};
+
 use ark_bls12_381::{Bls12_381};
 use ark_bn254::{Bn254};
+use ark_bls12_377::{Bls12_377};
+use ark_bw6_761::{BW6_761};
+use ark_mnt4_298::{MNT4_298};
+use ark_mnt6_298::{MNT6_298};
 
 #[test]
 fn test_kzg_commit_bls12_381() {
@@ -21,18 +33,18 @@ fn _test_kzg_commit<E: PairingEngine>() {
 
     for i in 0..scheme.k {
         // internal method
-        let commitment1 = scheme.commit_column(&data_uncoded, i);
+        let commitment1 = scheme.commit_column(&data_uncoded, 0, i);
 
         // explicit method
-        let poly_evals = Evaluations::from_vec_and_domain(data_uncoded.iter().map(|r| r[i]).collect(), scheme.domain_polycommit);
+        let poly_evals = Evaluations::from_vec_and_domain(data_uncoded.column(i).to_vec(), scheme.domain_polycommit);
         let poly_poly = poly_evals.interpolate();
-        let commitment2 = SemiAvidPr::unwrap_commitment(KZG10::commit(&scheme.kzg10_ck, &poly_poly, None, None).unwrap());
+        let commitment2 = Kzg::unwrap_commitment(KZG10::commit(&scheme.commitment_scheme.ck, &poly_poly, None, None).unwrap());
         
         // compute KZG commitment manually to double-check calculation
         let mut commitment3 = E::G1Projective::zero();
-        assert_eq!(scheme.kzg10_ck.powers_of_g.len(), poly_poly.coeffs.len());
-        for j in 0..scheme.kzg10_ck.powers_of_g.len() {
-            commitment3 += scheme.kzg10_ck.powers_of_g[j].mul(poly_poly.coeffs[j]);
+        assert_eq!(scheme.commitment_scheme.ck.powers_of_g.len(), poly_poly.coeffs.len());
+        for j in 0..scheme.commitment_scheme.ck.powers_of_g.len() {
+            commitment3 += scheme.commitment_scheme.ck.powers_of_g[j].mul(poly_poly.coeffs[j]);
         }
         let commitment3 = commitment3.into_affine();
 
@@ -59,11 +71,11 @@ fn _test_kzg_commitment_unwrap_wrap<E: PairingEngine>() {
     let scheme = SemiAvidPr::<E>::setup(&mut rng, 2, 1, 1024);
     let data_uncoded = scheme.generate_random_file(&mut rng);
 
-    let poly_evals = Evaluations::from_vec_and_domain(data_uncoded.iter().map(|r| r[0]).collect(), scheme.domain_polycommit);
+    let poly_evals = Evaluations::from_vec_and_domain(data_uncoded.column(0).to_vec(), scheme.domain_polycommit);
     let poly_poly = poly_evals.interpolate();
-    let commitment = KZG10::commit(&scheme.kzg10_ck, &poly_poly, None, None).unwrap();
+    let commitment = KZG10::commit(&scheme.commitment_scheme.ck, &poly_poly, None, None).unwrap();
 
-    assert_eq!(commitment, SemiAvidPr::wrap_commitment(SemiAvidPr::unwrap_commitment(commitment.clone())));
+    assert_eq!(commitment, Kzg::wrap_commitment(Kzg::unwrap_commitment(commitment.clone())));
 }
 
 #[test]
@@ -73,4 +85,1362 @@ fn test_filesizes() {
     assert_eq!(scheme.get_filesize_in_bytes(), 254 * 256*1024 / 8);
     let scheme = SemiAvidPr::<Bn254>::setup(&mut rng, 512, 256, 1024);
     assert_eq!(scheme.get_filesize_in_bytes(), 253 * 256*1024 / 8);
+    let scheme = SemiAvidPr::<Bls12_377>::setup(&mut rng, 16, 8, 64);
+    assert_eq!(scheme.get_filesize_in_bytes(), 252 * 8*64 / 8);
+    let scheme = SemiAvidPr::<BW6_761>::setup(&mut rng, 16, 8, 64);
+    assert_eq!(scheme.get_filesize_in_bytes(), 376 * 8*64 / 8);
+    let scheme = SemiAvidPr::<MNT4_298>::setup(&mut rng, 16, 8, 64);
+    assert_eq!(scheme.get_filesize_in_bytes(), 297 * 8*64 / 8);
+    let scheme = SemiAvidPr::<Bls12_381>::setup_with_segments(&mut rng, 16, 8, 64, 4);
+    assert_eq!(scheme.get_filesize_in_bytes(), 254 * 8*64*4 / 8);
+}
+
+#[test]
+fn test_segments_bls12_381() {
+    _test_segments::<Bls12_381>()
+}
+
+#[test]
+fn test_segments_bn254() {
+    _test_segments::<Bn254>()
+}
+
+#[test]
+fn test_segments_bls12_377() {
+    _test_segments::<Bls12_377>()
+}
+
+#[test]
+fn test_segments_bw6_761() {
+    _test_segments::<BW6_761>()
+}
+
+#[test]
+fn test_segments_mnt4_298() {
+    _test_segments::<MNT4_298>()
+}
+
+#[test]
+fn test_segments_mnt6_298() {
+    _test_segments::<MNT6_298>()
+}
+
+fn _test_segments<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 3);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    assert_eq!(data_uncoded.height(), 3 * 64);
+
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    assert_eq!(column_commitments.len(), scheme.get_num_column_commitments());
+    for s in 0..3 {
+        for i in 0..scheme.k {
+            assert_eq!(column_commitments[s*scheme.k + i], scheme.commit_column(&data_uncoded, s, i));
+        }
+    }
+
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+    assert!(scheme.disperse_verify_chunks(&column_commitments, &data_coded));
+
+    let idxs_download_nodes: Vec<usize> = (3..11).collect();
+    let data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, &idxs_download_nodes);
+    assert!(scheme.retrieve_verify_chunks(&column_commitments, &data_coded_downloaded, &idxs_download_nodes));
+    let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+    assert_eq!(scheme.retrieve_decode_rows(&data_coded_downloaded, &decoder_aux), data_uncoded);
+
+    for &(row, col) in &[(0, 0), (70, 3), (191, 7)] {
+        let opening = scheme.sampling_open_entry(&column_commitments, &data_uncoded, row, col);
+        assert!(scheme.sampling_verify_entry(opening));
+    }
+
+    // tampering with an entry in the last segment is detected
+    let mut data_coded_tampered = data_coded.clone();
+    data_coded_tampered[(150, 5)] += E::Fr::one();
+    assert!(!scheme.disperse_verify_chunks(&column_commitments, &data_coded_tampered));
+}
+
+#[test]
+fn test_streaming_dispersal_bls12_381() {
+    _test_streaming_dispersal::<Bls12_381>()
+}
+
+#[test]
+fn test_streaming_dispersal_bn254() {
+    _test_streaming_dispersal::<Bn254>()
+}
+
+fn _test_streaming_dispersal<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+
+    let mut stream = scheme.disperse_stream();
+    for j in 0..data_uncoded.height() {
+        let row: Vec<E::Fr> = data_uncoded.row(j).copied().collect();
+        assert_eq!(stream.push_row(&row), data_coded.row(j).copied().collect::<Vec<E::Fr>>());
+    }
+    assert_eq!(stream.get_num_rows_pushed(), scheme.get_num_rows());
+    assert_eq!(stream.finalize(), column_commitments);
+
+    // finalizing early commits to the zero-padded file
+    let num_rows_partial = 100;
+    let mut data_uncoded_partial = data_uncoded.clone();
+    for col in 0..scheme.k {
+        data_uncoded_partial.column_mut(col)[num_rows_partial..].iter_mut().for_each(|v| *v = E::Fr::zero());
+    }
+    let mut stream = scheme.disperse_stream();
+    for j in 0..num_rows_partial {
+        stream.push_row(&data_uncoded.row(j).copied().collect::<Vec<E::Fr>>());
+    }
+    assert_eq!(stream.finalize(), scheme.disperse_compute_column_commitments(&data_uncoded_partial));
+}
+
+#[test]
+fn test_update_entry_bls12_381() {
+    _test_update_entry::<Bls12_381>()
+}
+
+#[test]
+fn test_update_entry_bn254() {
+    _test_update_entry::<Bn254>()
+}
+
+fn _test_update_entry<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let mut data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let mut data_coded = scheme.disperse_encode_rows(&data_uncoded);
+
+    let (row, col) = (77, 5);
+    let new = E::Fr::rand(&mut rng);
+    let (column_commitments_new, update) = scheme.update_entry(&column_commitments, row, col, data_uncoded[(row, col)], new);
+    data_uncoded[(row, col)] = new;
+    assert_eq!(column_commitments_new, scheme.disperse_compute_column_commitments(&data_uncoded));
+
+    let data_coded_expected = scheme.disperse_encode_rows(&data_uncoded);
+    let deltas_coded = scheme.update_coded_deltas(&update);
+    for idx in 0..scheme.n {
+        assert_eq!(data_coded[(row, idx)] + deltas_coded[idx], data_coded_expected[(row, idx)]);
+    }
+
+    // nodes reject commitments that do not match the update, leaving their chunk untouched
+    let mut update_wrong = update;
+    update_wrong.delta += E::Fr::one();
+    assert!(!scheme.update_verify_and_apply(&column_commitments, &column_commitments_new, &update_wrong, data_coded.column_mut(0), 0));
+    let mut column_commitments_wrong = column_commitments_new.clone();
+    column_commitments_wrong.swap(0, 1);
+    assert!(!scheme.update_verify_and_apply(&column_commitments, &column_commitments_wrong, &update, data_coded.column_mut(0), 0));
+    // ... and malformed input from the client
+    assert!(!scheme.update_verify_and_apply(&column_commitments[1..], &column_commitments_new[1..], &update, data_coded.column_mut(0), 0));
+    assert!(!scheme.update_verify_and_apply(&column_commitments, &column_commitments_new, &update, &mut data_coded.column_mut(0)[1..], 0));
+
+    for idx in 0..scheme.n {
+        assert!(scheme.update_verify_and_apply(&column_commitments, &column_commitments_new, &update, data_coded.column_mut(idx), idx));
+    }
+    assert_eq!(data_coded, data_coded_expected);
+    assert!(scheme.disperse_verify_chunks(&column_commitments_new, &data_coded));
+}
+
+#[test]
+fn test_file_matrix() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let rows: Vec<Vec<<Bls12_381 as PairingEngine>::Fr>> = (0..5).map(|_| (0..3).map(|_| UniformRand::rand(&mut rng)).collect()).collect();
+    let matrix = FileMatrix::from_rows(5, 3, &rows);
+    assert_eq!(matrix.to_rows(), rows);
+
+    for col in 0..3 {
+        assert_eq!(matrix.column(col), rows.iter().map(|r| r[col]).collect::<Vec<_>>().as_slice());
+    }
+    for row in 0..5 {
+        assert_eq!(matrix.row(row).len(), 3);
+        assert_eq!(matrix.row(row).copied().collect::<Vec<_>>(), rows[row]);
+    }
+
+    let transposed = matrix.transpose();
+    assert_eq!((transposed.height(), transposed.width()), (3, 5));
+    for row in 0..5 {
+        assert_eq!(transposed.column(row), rows[row].as_slice());
+    }
+    assert_eq!(transposed.transpose(), matrix);
+
+    let picked = matrix.pick_columns(&[2, 0]);
+    assert_eq!(picked.column(0), matrix.column(2));
+    assert_eq!(picked.column(1), matrix.column(0));
+    assert_eq!(FileMatrix::from_columns(5, 3, matrix.columns().map(|c| c.to_vec()).collect()), matrix);
+}
+
+#[test]
+fn test_out_of_core_dispersal_bls12_381() {
+    _test_out_of_core_dispersal::<Bls12_381>()
+}
+
+#[test]
+fn test_out_of_core_dispersal_bn254() {
+    _test_out_of_core_dispersal::<Bn254>()
+}
+
+fn _test_out_of_core_dispersal<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+
+    let dir = std::env::temp_dir().join(format!("semiavidpr-test-out-of-core-{}", u64::rand(&mut rng)));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path_input = dir.join("file.bin");
+    outofcore::write_file_matrix::<E>(&path_input, &data_uncoded).unwrap();
+
+    // too small a budget is refused
+    assert!(scheme.disperse_out_of_core(&path_input, &dir.join("chunks"), 1024).is_err());
+
+    // a budget that forces several blocks of rows
+    let column_commitments = scheme.disperse_out_of_core(&path_input, &dir.join("chunks"), 40_000).unwrap();
+    assert_eq!(column_commitments, scheme.disperse_compute_column_commitments(&data_uncoded));
+
+    let chunks = (0..scheme.n).map(|idx| outofcore::read_chunk::<E>(&dir.join("chunks"), idx).unwrap()).collect();
+    let data_coded = FileMatrix::from_columns(scheme.get_num_rows(), scheme.n, chunks);
+    assert_eq!(data_coded, scheme.disperse_encode_rows(&data_uncoded));
+    assert!(scheme.disperse_verify_chunks(&column_commitments, &data_coded));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn _random_matrix<F: Field, R: Rng>(rng: &mut R, height: usize, width: usize) -> Matrix<F> {
+    Matrix::from_nested_vec(height, width, (0..height).map(|_| (0..width).map(|_| F::rand(rng)).collect()).collect())
+}
+
+fn _is_identity<F: Field>(matrix: &Matrix<F>) -> bool {
+    (0..matrix.height()).all(|row| (0..matrix.width()).all(|col| matrix.get(row, col) == if row == col { F::one() } else { F::zero() }))
+}
+
+fn _mul_naive<F: Field>(a: &Matrix<F>, b: &Matrix<F>) -> Matrix<F> {
+    Matrix::from_nested_vec(a.height(), b.width(), (0..a.height()).map(|row| (0..b.width()).map(|col| (0..a.width()).map(|i| a.get(row, i) * b.get(i, col)).sum()).collect()).collect())
+}
+
+#[test]
+fn test_matrix_invert_with_pivoting() {
+    type F = <Bls12_381 as PairingEngine>::Fr;
+    let mut rng = ark_std::rand::thread_rng();
+
+    // invertible, but all leading minors vanish
+    let matrix = Matrix::<F>::from_nested_vec(3, 3, vec![
+        vec![F::zero(), F::one(), F::zero()],
+        vec![F::zero(), F::zero(), F::one()],
+        vec![F::one(), F::zero(), F::zero()],
+    ]);
+    assert!(_is_identity(&_mul_naive(&matrix, &matrix.invert().unwrap())));
+    assert!(_is_identity(&_mul_naive(&matrix, &matrix.lu().unwrap().inverse())));
+    assert_eq!(matrix.determinant(), F::one());
+    assert_eq!(matrix.rank(), 3);
+
+    for d in [1, 2, 5, 16] {
+        let matrix = _random_matrix::<F, _>(&mut rng, d, d);
+        let inverse = matrix.invert().unwrap();
+        assert!(_is_identity(&_mul_naive(&matrix, &inverse)));
+        assert!(_is_identity(&_mul_naive(&inverse, &matrix)));
+        assert_eq!(matrix.lu().unwrap().inverse(), inverse);
+        assert_eq!(matrix.determinant() * inverse.determinant(), F::one());
+    }
+}
+
+#[test]
+fn test_matrix_singular() {
+    type F = <Bls12_381 as PairingEngine>::Fr;
+    let mut rng = ark_std::rand::thread_rng();
+
+    // third row is the sum of the first two
+    let mut matrix = _random_matrix::<F, _>(&mut rng, 3, 3);
+    for col in 0..3 {
+        matrix.set(2, col, matrix.get(0, col) + matrix.get(1, col));
+    }
+    assert_eq!(matrix.invert().unwrap_err(), SingularMatrix);
+    assert!(matrix.lu().is_err());
+    assert!(matrix.solve(&[F::one(), F::one(), F::one()]).is_err());
+    assert_eq!(matrix.rank(), 2);
+    assert_eq!(matrix.determinant(), F::zero());
+
+    assert_eq!(Matrix::<F>::from_nested_vec(2, 3, vec![vec![F::zero(); 3]; 2]).rank(), 0);
+    assert_eq!(_random_matrix::<F, _>(&mut rng, 4, 7).rank(), 4);
+}
+
+#[test]
+fn test_matrix_solve() {
+    type F = <Bls12_381 as PairingEngine>::Fr;
+    let mut rng = ark_std::rand::thread_rng();
+
+    let matrix = _random_matrix::<F, _>(&mut rng, 8, 8);
+    let lu = matrix.lu().unwrap();
+
+    let x: Vec<F> = (0..8).map(|_| F::rand(&mut rng)).collect();
+    let rhs = _mul_naive(&matrix, &Matrix::from_nested_vec(8, 1, x.iter().map(|&v| vec![v]).collect()));
+    let rhs: Vec<F> = (0..8).map(|row| rhs.get(row, 0)).collect();
+    assert_eq!(lu.solve(&rhs), x);
+    assert_eq!(matrix.solve(&rhs).unwrap(), x);
+
+    let xs = _random_matrix::<F, _>(&mut rng, 8, 3);
+    let rhs = _mul_naive(&matrix, &xs);
+    assert_eq!(matrix.solve_many(&rhs).unwrap(), xs);
+}
+
+#[test]
+fn test_vandermonde_inverse_bls12_381() {
+    _test_vandermonde_inverse::<Bls12_381>()
+}
+
+#[test]
+fn test_vandermonde_inverse_bn254() {
+    _test_vandermonde_inverse::<Bn254>()
+}
+
+fn _test_vandermonde_inverse<E: PairingEngine>() {
+    use rand::seq::SliceRandom;
+    let mut rng = ark_std::rand::thread_rng();
+
+    let (n, k) = (64, 21);
+    let domain_encoding: GeneralEvaluationDomain<E::Fr> = EvaluationDomain::<E::Fr>::new(n).unwrap();
+
+    for _ in 0..10 {
+        let mut idxs: Vec<usize> = (0..n).collect();
+        idxs.shuffle(&mut rng);
+        idxs.truncate(k);
+
+        let points: Vec<E::Fr> = idxs.iter().map(|&j| domain_encoding.element(j)).collect();
+        let matrix = Matrix::from_nested_vec(k, k, (0..k).map(|i| points.iter().map(|x| x.pow([i as u64])).collect()).collect());
+        assert_eq!(vandermonde_inverse(&points).unwrap(), matrix.invert().unwrap());
+    }
+
+    let mut points: Vec<E::Fr> = (0..5).map(|_| E::Fr::rand(&mut rng)).collect();
+    points[3] = points[1];
+    assert_eq!(vandermonde_inverse(&points).unwrap_err(), SingularMatrix);
+}
+
+#[test]
+fn test_matrix_algebra() {
+    type F = <Bls12_381 as PairingEngine>::Fr;
+    let mut rng = ark_std::rand::thread_rng();
+
+    let a = _random_matrix::<F, _>(&mut rng, 4, 6);
+    let b = _random_matrix::<F, _>(&mut rng, 6, 3);
+    assert_eq!(a.mul(&b), _mul_naive(&a, &b));
+    assert_eq!(a.mul(&Matrix::identity(6)), a);
+    assert_eq!(Matrix::identity(4).mul(&a), a);
+    assert!(_is_identity(&Matrix::<F>::identity(5)));
+
+    let v: Vec<F> = (0..6).map(|_| F::rand(&mut rng)).collect();
+    let v_as_matrix = Matrix::from_nested_vec(6, 1, v.iter().map(|&x| vec![x]).collect());
+    assert_eq!(a.mul_vec(&v), a.mul(&v_as_matrix).column(0).copied().collect::<Vec<F>>());
+
+    let t = a.transpose();
+    assert_eq!((t.height(), t.width()), (6, 4));
+    for row in 0..4 {
+        for col in 0..6 {
+            assert_eq!(t[(col, row)], a[(row, col)]);
+            assert_eq!(a[(row, col)], a.get(row, col));
+        }
+        assert_eq!(a.row(row), t.column(row).copied().collect::<Vec<F>>().as_slice());
+    }
+    assert_eq!(t.transpose(), a);
+    assert_eq!(a.mul(&b).transpose(), b.transpose().mul(&t));
+
+    let s = a.slice(1..3, 2..6);
+    assert_eq!((s.height(), s.width()), (2, 4));
+    assert_eq!(s.row(1), &a.row(2)[2..6]);
+    assert_eq!(a.hcat(&a).hpick(&[0, 1, 2, 3, 4, 5]), a);
+
+    let mut m = Matrix::<F>::zeros(2, 2);
+    m[(0, 1)] = F::one();
+    assert_eq!(m.get(0, 1), F::one());
+
+    // column-major files multiply the same as row-major matrices
+    let file = FileMatrix::from_rows(4, 6, &(0..4).map(|row| a.row(row).to_vec()).collect::<Vec<Vec<F>>>());
+    let product = a.mul(&b);
+    assert_eq!(file.mul(&b).to_rows(), (0..4).map(|row| product.row(row).to_vec()).collect::<Vec<Vec<F>>>());
+}
+
+#[test]
+fn test_decoder_cache() {
+    type F = <Bls12_381 as PairingEngine>::Fr;
+
+    let mut cache = DecoderCache::<F>::new(2);
+    assert!(cache.get(&[1, 2]).is_none());
+    cache.insert(vec![1, 2], Matrix::identity(1));
+    cache.insert(vec![3, 4], Matrix::identity(2));
+    assert_eq!(cache.get(&[1, 2]), Some(Matrix::identity(1)));
+
+    // [3, 4] is least recently used and gets evicted
+    cache.insert(vec![5, 6], Matrix::identity(3));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(&[3, 4]).is_none());
+    assert!(cache.get(&[1, 2]).is_some());
+    assert!(cache.get(&[5, 6]).is_some());
+    assert_eq!((cache.get_num_hits(), cache.get_num_misses()), (3, 2));
+
+    cache.set_capacity(0);
+    assert!(cache.is_empty());
+    cache.insert(vec![1, 2], Matrix::identity(1));
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_decoder_cache_retrieval_bls12_381() {
+    _test_decoder_cache_retrieval::<Bls12_381>()
+}
+
+#[test]
+fn test_decoder_cache_retrieval_bn254() {
+    _test_decoder_cache_retrieval::<Bn254>()
+}
+
+fn _test_decoder_cache_retrieval<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup(&mut rng, 16, 8, 64);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+
+    let idxs_download_nodes = vec![9, 2, 14, 5, 0, 11, 7, 3];
+    let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+    assert_eq!(scheme.get_decoder_cache_stats(), (0, 1));
+
+    // same subset in another order hits the cache, with rows permuted accordingly
+    let idxs_download_nodes_permuted = vec![0, 2, 3, 5, 7, 9, 11, 14];
+    let decoder_aux_permuted = scheme.retrieve_prepare_decoding(&idxs_download_nodes_permuted);
+    assert_eq!(scheme.get_decoder_cache_stats(), (1, 1));
+    assert_eq!(decoder_aux, scheme.retrieve_prepare_decoding(&idxs_download_nodes));
+    assert_eq!(scheme.get_decoder_cache_stats(), (2, 1));
+
+    for (idxs, aux) in [(&idxs_download_nodes, &decoder_aux), (&idxs_download_nodes_permuted, &decoder_aux_permuted)] {
+        let data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, idxs);
+        assert_eq!(scheme.retrieve_decode_rows(&data_coded_downloaded, aux), data_uncoded);
+    }
+
+    scheme.set_decoder_cache_capacity(0);
+    scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+    assert_eq!(scheme.get_decoder_cache_stats(), (2, 2));
+}
+
+#[test]
+fn test_partial_retrieval_bls12_381() {
+    _test_partial_retrieval::<Bls12_381>()
+}
+
+#[test]
+fn test_partial_retrieval_bn254() {
+    _test_partial_retrieval::<Bn254>()
+}
+
+fn _test_partial_retrieval<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    // the default setup leaves out the powers of h in G2
+    assert!(SemiAvidPr::<E>::setup(&mut rng, 16, 8, 64).commitment_scheme.neg_powers_of_h.is_empty());
+
+    let scheme = SemiAvidPr::<E>::setup_for_partial_retrieval(&mut rng, 16, 8, 64, 3);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+
+    let idxs_download_nodes: Vec<usize> = vec![1, 4, 6, 7, 10, 12, 13, 15];
+    let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+
+    // within a segment, across segments, and a complete segment plus some
+    for rows in [3..4, 10..30, 50..70, 60..140] {
+        let openings: Vec<RowsOpening<E>> = idxs_download_nodes.iter().map(|&idx| scheme.retrieve_open_rows(&data_coded, idx, rows.clone())).collect();
+        assert!(scheme.retrieve_verify_rows(&column_commitments, &openings));
+
+        let data_decoded = scheme.retrieve_decode_partial_rows(&openings, &decoder_aux);
+        assert_eq!(data_decoded.height(), rows.len());
+        for (row_decoded, row) in rows.clone().enumerate() {
+            assert!(data_decoded.row(row_decoded).eq(data_uncoded.row(row)));
+        }
+
+        let mut openings_tampered = openings.clone();
+        openings_tampered[2].values[0] += E::Fr::one();
+        assert!(!scheme.retrieve_verify_rows(&column_commitments, &openings_tampered));
+
+        let mut openings_tampered = openings.clone();
+        openings_tampered[5].idx = 0;
+        assert!(!scheme.retrieve_verify_rows(&column_commitments, &openings_tampered));
+    }
+
+    // openings from several nodes must cover the same rows
+    let openings = vec![scheme.retrieve_open_rows(&data_coded, 0, 10..20), scheme.retrieve_open_rows(&data_coded, 1, 11..21)];
+    assert!(!scheme.retrieve_verify_rows(&column_commitments, &openings));
+
+    // too few column commitments (e.g., from the network) are rejected, not a panic
+    let openings = vec![scheme.retrieve_open_rows(&data_coded, 0, 10..20)];
+    assert!(!scheme.retrieve_verify_rows(&column_commitments[..column_commitments.len() - 1], &openings));
+}
+
+#[test]
+fn test_redundant_retrieval_bls12_381() {
+    _test_redundant_retrieval::<Bls12_381>()
+}
+
+#[test]
+fn test_redundant_retrieval_bn254() {
+    _test_redundant_retrieval::<Bn254>()
+}
+
+fn _test_redundant_retrieval<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup(&mut rng, 16, 8, 64);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+
+    let idxs_download_nodes: Vec<usize> = vec![12, 0, 3, 9, 5, 14, 1, 7, 10, 2, 11];
+    let data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, &idxs_download_nodes);
+
+    // nothing cached yet, so the first k chunks are used
+    let (data_decoded, report) = scheme.retrieve_decode_rows_redundant(&data_coded_downloaded, &idxs_download_nodes, RedundantRetrievalMode::PickSubset).unwrap();
+    assert_eq!(data_decoded, data_uncoded);
+    assert_eq!(report, RedundantRetrievalReport::SubsetPicked { idxs_used: idxs_download_nodes[..8].to_vec(), decoder_cached: false });
+
+    // a subset with a cached decoding matrix is preferred
+    scheme.retrieve_prepare_decoding(&vec![0, 1, 2, 3, 5, 7, 9, 10]);
+    let (data_decoded, report) = scheme.retrieve_decode_rows_redundant(&data_coded_downloaded, &idxs_download_nodes, RedundantRetrievalMode::PickSubset).unwrap();
+    assert_eq!(data_decoded, data_uncoded);
+    assert_eq!(report, RedundantRetrievalReport::SubsetPicked { idxs_used: vec![0, 1, 2, 3, 5, 7, 9, 10], decoder_cached: true });
+
+    let (data_decoded, report) = scheme.retrieve_decode_rows_redundant(&data_coded_downloaded, &idxs_download_nodes, RedundantRetrievalMode::CrossCheck).unwrap();
+    assert_eq!(data_decoded, data_uncoded);
+    assert_eq!(report, RedundantRetrievalReport::CrossChecked { idxs_used: vec![0, 1, 2, 3, 5, 7, 9, 10], idxs_checked: vec![12, 14, 11] });
+
+    // a chunk that is not part of the codeword is detected
+    let mut data_coded_downloaded_tampered = data_coded_downloaded.clone();
+    data_coded_downloaded_tampered[(17, 5)] += E::Fr::one();
+    let err = scheme.retrieve_decode_rows_redundant(&data_coded_downloaded_tampered, &idxs_download_nodes, RedundantRetrievalMode::CrossCheck).unwrap_err();
+    assert_eq!(err.idxs_inconsistent, vec![14]);
+}
+
+#[test]
+fn test_repair_chunk_bls12_381() {
+    _test_repair_chunk::<Bls12_381>()
+}
+
+#[test]
+fn test_repair_chunk_bn254() {
+    _test_repair_chunk::<Bn254>()
+}
+
+fn _test_repair_chunk<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+
+    let idxs_download_nodes: Vec<usize> = vec![15, 2, 8, 4, 11, 6, 0, 13];
+    let data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, &idxs_download_nodes);
+    assert!(scheme.retrieve_verify_chunks(&column_commitments, &data_coded_downloaded, &idxs_download_nodes));
+
+    for idx_target in [1, 2, 9] {
+        let chunk = scheme.repair_compute_chunk(&data_coded_downloaded, &idxs_download_nodes, idx_target);
+        assert_eq!(chunk, data_coded.column(idx_target));
+        assert!(scheme.repair_verify_chunk(&column_commitments, &chunk, idx_target));
+        assert!(!scheme.repair_verify_chunk(&column_commitments, &chunk, idx_target + 1));
+        assert!(!scheme.repair_verify_chunk(&column_commitments[..column_commitments.len()-1], &chunk, idx_target));
+    }
+
+    // a repair from a corrupted chunk is rejected
+    let mut data_coded_downloaded_tampered = data_coded_downloaded.clone();
+    data_coded_downloaded_tampered[(100, 3)] += E::Fr::one();
+    let chunk = scheme.repair_compute_chunk(&data_coded_downloaded_tampered, &idxs_download_nodes, 1);
+    assert!(!scheme.repair_verify_chunk(&column_commitments, &chunk, 1));
+}
+
+#[test]
+fn test_lrc_repair_bls12_381() {
+    _test_lrc_repair::<Bls12_381>()
+}
+
+#[test]
+fn test_lrc_repair_bn254() {
+    _test_lrc_repair::<Bn254>()
+}
+
+fn _test_lrc_repair<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+
+    let layout = LrcLayout::new(16, 4);
+    assert_eq!(layout.num_groups(), 4);
+    assert_eq!(layout.repair_sources(6), vec![4, 5, 7]);
+    assert_eq!(layout.get_num_repair_downloads(), 4);
+
+    let parities = scheme.lrc_encode_parities(&layout, &data_coded);
+    for group in 0..layout.num_groups() {
+        assert!(scheme.lrc_verify_parity(&layout, &column_commitments, parities.column(group), group));
+    }
+    assert!(!scheme.lrc_verify_parity(&layout, &column_commitments, parities.column(0), 1));
+
+    for idx_target in [0, 6, 15] {
+        let idxs_repair_nodes = layout.repair_sources(idx_target);
+        let data_coded_group = scheme.retrieve_download_chunks(&data_coded, &idxs_repair_nodes);
+        let chunk = scheme.lrc_repair_chunk(&layout, &data_coded_group, &idxs_repair_nodes, parities.column(layout.group_of(idx_target)), idx_target);
+        assert_eq!(chunk, data_coded.column(idx_target));
+        assert!(scheme.repair_verify_chunk(&column_commitments, &chunk, idx_target));
+    }
+
+    // the other members of the group can be downloaded in any order
+    let idxs_repair_nodes = vec![7, 4, 5];
+    let data_coded_group = scheme.retrieve_download_chunks(&data_coded, &idxs_repair_nodes);
+    assert_eq!(scheme.lrc_repair_chunk(&layout, &data_coded_group, &idxs_repair_nodes, parities.column(1), 6), data_coded.column(6));
+}
+
+#[test]
+fn test_audit_bls12_381() {
+    _test_audit::<Bls12_381>()
+}
+
+#[test]
+fn test_audit_bn254() {
+    _test_audit::<Bn254>()
+}
+
+fn _test_audit<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+
+    for challenge in [scheme.audit_challenge_rows(&mut rng, 3), scheme.audit_challenge_point(&mut rng)] {
+        let response = scheme.audit_respond(data_coded.column(5), &challenge).unwrap();
+        assert!(scheme.audit_verify(&column_commitments, 5, &challenge, &response));
+        assert!(!scheme.audit_verify(&column_commitments, 6, &challenge, &response));
+
+        let mut response_tampered = response.clone();
+        response_tampered.values[0] += E::Fr::one();
+        assert!(!scheme.audit_verify(&column_commitments, 5, &challenge, &response_tampered));
+    }
+
+    // challenges beyond the chunk are refused rather than crashing the node
+    let challenge = AuditChallenge::Rows(vec![3, scheme.get_num_rows()]);
+    assert_eq!(scheme.audit_respond(data_coded.column(5), &challenge).unwrap_err(), InvalidChallenge { row: scheme.get_num_rows() });
+
+    // node 3 lost part of its chunk, node 10 stores a chunk of another node
+    let mut data_stored = data_coded.clone();
+    for row in 0..scheme.get_num_rows() / 2 {
+        data_stored[(row, 3)] = E::Fr::zero();
+    }
+    for row in 0..scheme.get_num_rows() {
+        data_stored[(row, 10)] = data_coded[(row, 11)];
+    }
+
+    let mut scheduler = AuditScheduler::new(16, 6);
+    assert_eq!(scheduler.next_round(), vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(scheduler.next_round(), vec![6, 7, 8, 9, 10, 11]);
+    assert_eq!(scheduler.next_round(), vec![12, 13, 14, 15, 0, 1]);
+    let mut scheduler = AuditScheduler::new(16, 8);
+    let stats = scheme.audit_simulate(&mut rng, &column_commitments, &data_stored, &mut scheduler, 4, None);
+    assert_eq!(stats.num_audits, 32);
+    assert_eq!(stats.num_failed, 4);
+    assert_eq!(scheduler.failing_nodes(), vec![3, 10]);
+    assert_eq!(scheduler.stats(3), AuditStats { num_audits: 2, num_passed: 0, num_failed: 2 });
+
+    // row challenges catch the loss as soon as they hit a lost row
+    let mut scheduler = AuditScheduler::new(16, 16);
+    scheme.audit_simulate(&mut rng, &column_commitments, &data_stored, &mut scheduler, 1, Some(16));
+    assert_eq!(scheduler.failing_nodes(), vec![3, 10]);
+}
+
+#[test]
+fn test_check_decoded_bls12_381() {
+    _test_check_decoded::<Bls12_381>()
+}
+
+#[test]
+fn test_check_decoded_bn254() {
+    _test_check_decoded::<Bn254>()
+}
+
+fn _test_check_decoded<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+
+    // decoding from chunks that were not checked individually
+    let idxs_download_nodes: Vec<usize> = vec![1, 3, 5, 7, 9, 11, 13, 15];
+    let mut data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, &idxs_download_nodes);
+    let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+    let data_decoded = scheme.retrieve_decode_rows(&data_coded_downloaded, &decoder_aux);
+
+    for mode in [DecodedCheckMode::Recommit, DecodedCheckMode::RandomLinearCombination] {
+        assert_eq!(scheme.retrieve_check_decoded(&mut rng, &column_commitments, &data_decoded, mode), Ok(()));
+    }
+
+    // a corrupted entry in the second segment of one chunk spreads over all decoded columns
+    data_coded_downloaded[(100, 2)] += E::Fr::one();
+    let data_decoded = scheme.retrieve_decode_rows(&data_coded_downloaded, &decoder_aux);
+
+    let mismatch = scheme.retrieve_check_decoded(&mut rng, &column_commitments, &data_decoded, DecodedCheckMode::Recommit).unwrap_err();
+    assert_eq!(mismatch.segments, vec![1]);
+    assert_eq!(mismatch.columns, Some((0..8).map(|i| (1, i)).collect()));
+
+    let mismatch = scheme.retrieve_check_decoded(&mut rng, &column_commitments, &data_decoded, DecodedCheckMode::RandomLinearCombination).unwrap_err();
+    assert_eq!(mismatch, DecodingMismatch { segments: vec![1], columns: None });
+}
+
+#[test]
+fn test_commitment_scheme_kzg_bls12_381() {
+    _test_commitment_scheme::<Bls12_381, Kzg<Bls12_381>>()
+}
+
+#[test]
+fn test_commitment_scheme_kzg_bn254() {
+    _test_commitment_scheme::<Bn254, Kzg<Bn254>>()
+}
+
+#[test]
+fn test_commitment_scheme_kzg_bls12_377() {
+    _test_commitment_scheme::<Bls12_377, Kzg<Bls12_377>>()
+}
+
+#[test]
+fn test_commitment_scheme_kzg_bw6_761() {
+    _test_commitment_scheme::<BW6_761, Kzg<BW6_761>>()
+}
+
+#[test]
+fn test_commitment_scheme_kzg_mnt4_298() {
+    _test_commitment_scheme::<MNT4_298, Kzg<MNT4_298>>()
+}
+
+#[test]
+fn test_commitment_scheme_kzg_mnt6_298() {
+    _test_commitment_scheme::<MNT6_298, Kzg<MNT6_298>>()
+}
+
+#[test]
+fn test_commitment_scheme_pedersen_bls12_381() {
+    _test_commitment_scheme::<Bls12_381, Pedersen<<Bls12_381 as PairingEngine>::G1Affine>>()
+}
+
+#[test]
+fn test_commitment_scheme_pedersen_bn254() {
+    _test_commitment_scheme::<Bn254, Pedersen<<Bn254 as PairingEngine>::G1Affine>>()
+}
+
+#[test]
+fn test_commitment_scheme_pedersen_bls12_377() {
+    _test_commitment_scheme::<Bls12_377, Pedersen<<Bls12_377 as PairingEngine>::G1Affine>>()
+}
+
+#[test]
+fn test_commitment_scheme_pedersen_bw6_761() {
+    _test_commitment_scheme::<BW6_761, Pedersen<<BW6_761 as PairingEngine>::G1Affine>>()
+}
+
+#[test]
+fn test_commitment_scheme_pedersen_mnt4_298() {
+    _test_commitment_scheme::<MNT4_298, Pedersen<<MNT4_298 as PairingEngine>::G1Affine>>()
+}
+
+#[test]
+fn test_commitment_scheme_pedersen_mnt6_298() {
+    _test_commitment_scheme::<MNT6_298, Pedersen<<MNT6_298 as PairingEngine>::G1Affine>>()
+}
+
+fn _test_commitment_scheme<E: PairingEngine, C: ColumnCommitmentScheme<E::Fr>>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E, C>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+    let pc = scheme.get_commitment_scheme();
+
+    // the FFT-based encoding of KZG agrees with the generic one by linear combinations
+    let coded_chunk_commitments = scheme.encode_commitments_all(&column_commitments);
+    for s in 0..2 {
+        for idx in 0..16 {
+            let commitment = pc.combine(&column_commitments[s*8..(s+1)*8], &scheme.encoding_coefficients(idx));
+            assert_eq!(commitment, coded_chunk_commitments[s][idx]);
+            assert_eq!(commitment, pc.commit(scheme.column_segment(&data_coded, s, idx)));
+        }
+    }
+
+    // openings at points inside and outside of the domain
+    let point = E::Fr::rand(&mut rng);
+    let (value, proof) = pc.open(scheme.column_segment(&data_uncoded, 1, 2), point);
+    assert!(pc.verify(&column_commitments[8 + 2], point, value, &proof));
+    assert!(!pc.verify(&column_commitments[8 + 2], point, value + E::Fr::one(), &proof));
+    assert!(!pc.verify(&column_commitments[8 + 3], point, value, &proof));
+
+    let openings: Vec<_> = [(0, 0), (70, 3), (100, 7), (127, 1)].iter()
+        .map(|&(row, col)| scheme.sampling_open_entry(&column_commitments, &data_uncoded, row, col))
+        .collect();
+    assert!(scheme.sampling_verify_entries(&mut rng, &openings));
+
+    let mut openings_tampered = openings.clone();
+    openings_tampered[2].0 += E::Fr::one();
+    assert!(!scheme.sampling_verify_entries(&mut rng, &openings_tampered));
+}
+
+#[test]
+fn test_pedersen_dispersal_bls12_381() {
+    _test_pedersen_dispersal::<Bls12_381>()
+}
+
+#[test]
+fn test_pedersen_dispersal_bn254() {
+    _test_pedersen_dispersal::<Bn254>()
+}
+
+fn _test_pedersen_dispersal<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    // the generators do not depend on the randomness of the setup
+    let scheme = SemiAvidPr::<E, Pedersen<E::G1Affine>>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let scheme2 = SemiAvidPr::<E, Pedersen<E::G1Affine>>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    assert_eq!(column_commitments, scheme2.disperse_compute_column_commitments(&data_uncoded));
+
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+    assert!(scheme.disperse_verify_chunks(&column_commitments, &data_coded));
+
+    let idxs_download_nodes: Vec<usize> = vec![2, 4, 6, 8, 10, 12, 14, 15];
+    let data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, &idxs_download_nodes);
+    assert!(scheme.retrieve_verify_chunks(&column_commitments, &data_coded_downloaded, &idxs_download_nodes));
+    let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+    assert_eq!(scheme.retrieve_decode_rows(&data_coded_downloaded, &decoder_aux), data_uncoded);
+
+    let opening = scheme.sampling_open_entry(&column_commitments, &data_uncoded, 70, 3);
+    assert_eq!(opening.4.l.len(), 6);
+    assert!(scheme.sampling_verify_entry(opening.clone()));
+    let mut opening_tampered = opening;
+    opening_tampered.4.a += E::Fr::one();
+    assert!(!scheme.sampling_verify_entry(opening_tampered));
+
+    let mut data_coded_tampered = data_coded.clone();
+    data_coded_tampered[(100, 5)] += E::Fr::one();
+    assert!(!scheme.disperse_verify_chunks(&column_commitments, &data_coded_tampered));
+}
+
+#[test]
+fn test_hiding_bls12_381() {
+    _test_hiding::<Bls12_381>()
+}
+
+#[test]
+fn test_hiding_bn254() {
+    _test_hiding::<Bn254>()
+}
+
+fn _test_hiding<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let (column_commitments, blindings) = scheme.disperse_compute_column_commitments_hiding(&mut rng, &data_uncoded, 4);
+    assert!(blindings.iter().all(|r| r.is_hiding()));
+
+    // commitments to the same file are fresh every time, and differ from the binding ones
+    let (column_commitments2, _) = scheme.disperse_compute_column_commitments_hiding(&mut rng, &data_uncoded, 4);
+    let column_commitments_binding = scheme.disperse_compute_column_commitments(&data_uncoded);
+    for i in 0..column_commitments.len() {
+        assert!(column_commitments[i] != column_commitments2[i]);
+        assert!(column_commitments[i] != column_commitments_binding[i]);
+    }
+
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+    let chunk_blindings = scheme.disperse_encode_blindings(&blindings);
+    assert!(scheme.disperse_verify_chunks_hiding(&column_commitments, &data_coded, &chunk_blindings));
+    assert!(!scheme.disperse_verify_chunks(&column_commitments, &data_coded));
+
+    let mut data_coded_tampered = data_coded.clone();
+    data_coded_tampered[(100, 5)] += E::Fr::one();
+    assert!(!scheme.disperse_verify_chunks_hiding(&column_commitments, &data_coded_tampered, &chunk_blindings));
+    let mut chunk_blindings_tampered = chunk_blindings.clone();
+    chunk_blindings_tampered[5][1] = chunk_blindings[4][1].clone();
+    assert!(!scheme.disperse_verify_chunks_hiding(&column_commitments, &data_coded, &chunk_blindings_tampered));
+
+    let idxs_download_nodes: Vec<usize> = vec![15, 2, 4, 6, 8, 10, 12, 14];
+    let data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, &idxs_download_nodes);
+    let chunk_blindings_downloaded: Vec<_> = idxs_download_nodes.iter().map(|&i| chunk_blindings[i].clone()).collect();
+    assert!(scheme.retrieve_verify_chunks_hiding(&column_commitments, &data_coded_downloaded, &chunk_blindings_downloaded, &idxs_download_nodes));
+    assert!(!scheme.retrieve_verify_chunks_hiding(&column_commitments, &data_coded_downloaded, &chunk_blindings[..8], &idxs_download_nodes));
+    let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+    assert_eq!(scheme.retrieve_decode_rows(&data_coded_downloaded, &decoder_aux), data_uncoded);
+
+    // sampling proofs carry the evaluation of the blinding, checked with gamma_g
+    let openings: Vec<_> = [(70, 3), (3, 0), (127, 7)].iter()
+        .map(|&(row, col)| scheme.sampling_open_entry_hiding(&column_commitments, &data_uncoded, &blindings, row, col))
+        .collect();
+    for opening in openings.iter() {
+        assert!(opening.4.random_v.is_some());
+        assert!(scheme.sampling_verify_entry(opening.clone()));
+    }
+    assert!(scheme.sampling_verify_entries(&mut rng, &openings));
+
+    let mut opening_tampered = openings[0].clone();
+    opening_tampered.4.random_v = Some(opening_tampered.4.random_v.unwrap() + E::Fr::one());
+    assert!(!scheme.sampling_verify_entry(opening_tampered));
+    let mut opening_tampered = openings[0].clone();
+    opening_tampered.4.random_v = None;
+    assert!(!scheme.sampling_verify_entry(opening_tampered));
+}
+
+#[test]
+fn test_confidential_bls12_381() {
+    _test_confidential::<Bls12_381>()
+}
+
+#[test]
+fn test_confidential_bn254() {
+    _test_confidential::<Bn254>()
+}
+
+fn _test_confidential<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let layout = ConfidentialLayout::new(6, 2);
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, layout.code_dimension(), 64, 2);
+    let data_payload = scheme.confidential_unmask_file(&layout, &scheme.generate_random_file(&mut rng));
+    assert_eq!(data_payload.width(), 6);
+
+    let data_masked = scheme.confidential_mask_file(&mut rng, &layout, &data_payload);
+    let (column_commitments, blindings) = scheme.disperse_compute_column_commitments_hiding(&mut rng, &data_masked, 4);
+    let data_coded = scheme.disperse_encode_rows(&data_masked);
+    let chunk_blindings = scheme.disperse_encode_blindings(&blindings);
+    assert!(scheme.disperse_verify_chunks_hiding(&column_commitments, &data_coded, &chunk_blindings));
+
+    // retrieval from k + z nodes
+    let idxs_download_nodes: Vec<usize> = vec![0, 3, 5, 6, 9, 10, 12, 15];
+    let data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, &idxs_download_nodes);
+    let chunk_blindings_downloaded: Vec<_> = idxs_download_nodes.iter().map(|&i| chunk_blindings[i].clone()).collect();
+    assert!(scheme.retrieve_verify_chunks_hiding(&column_commitments, &data_coded_downloaded, &chunk_blindings_downloaded, &idxs_download_nodes));
+    let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+    let data_decoded = scheme.retrieve_decode_rows(&data_coded_downloaded, &decoder_aux);
+    assert_eq!(data_decoded, data_masked);
+    assert_eq!(scheme.confidential_unmask_file(&layout, &data_decoded), data_payload);
+
+    // the chunks of any z nodes are explained equally well by any other payload: solve for the
+    // random columns that map the other payload to the same chunks
+    let idxs_colluding = [4, 11];
+    let data_payload_other = scheme.confidential_unmask_file(&layout, &scheme.generate_random_file(&mut rng));
+    let points: Vec<E::Fr> = idxs_colluding.iter().map(|&j| scheme.domain_encoding.element(j)).collect();
+    let system = Matrix::from_nested_vec(2, 2, points.iter().map(|x| layout.random_columns().map(|i| x.pow([i as u64])).collect()).collect());
+    let rows_random: Vec<Vec<E::Fr>> = (0..scheme.get_num_rows()).map(|r| {
+        let rhs: Vec<E::Fr> = idxs_colluding.iter().zip(points.iter()).map(|(&j, x)| {
+            data_coded.get(r, j) - (0..6).map(|i| x.pow([i as u64]) * data_payload_other.get(r, i)).sum::<E::Fr>()
+        }).collect();
+        system.solve(&rhs).unwrap()
+    }).collect();
+    let columns = data_payload_other.columns().map(|c| c.to_vec())
+        .chain((0..2).map(|t| rows_random.iter().map(|row| row[t]).collect()))
+        .collect();
+    let data_coded_other = scheme.disperse_encode_rows(&FileMatrix::from_columns(scheme.get_num_rows(), 8, columns));
+    for &j in idxs_colluding.iter() {
+        assert_eq!(data_coded_other.column(j), data_coded.column(j));
+    }
+    assert!(data_coded_other.column(0) != data_coded.column(0));
+}
+
+struct DispersalRoundTrip;
+
+impl CurveVisitor for DispersalRoundTrip {
+    type Output = ();
+
+    fn visit<E: NamedCurve>(self) {
+        let mut rng = ark_std::rand::thread_rng();
+
+        let scheme = SemiAvidPr::<E>::setup(&mut rng, 8, 4, 16);
+        let data_uncoded = scheme.generate_random_file(&mut rng);
+        let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+        let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+        assert!(scheme.disperse_verify_chunks(&column_commitments, &data_coded), "{}", E::NAME);
+
+        let idxs_download_nodes: Vec<usize> = vec![7, 1, 4, 2];
+        let data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, &idxs_download_nodes);
+        assert!(scheme.retrieve_verify_chunks(&column_commitments, &data_coded_downloaded, &idxs_download_nodes), "{}", E::NAME);
+        let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+        assert_eq!(scheme.retrieve_decode_rows(&data_coded_downloaded, &decoder_aux), data_uncoded, "{}", E::NAME);
+
+        assert!(scheme.sampling_verify_entry(scheme.sampling_open_entry(&column_commitments, &data_uncoded, 5, 3)), "{}", E::NAME);
+    }
+}
+
+#[test]
+fn test_all_curves() {
+    for curve in Curve::ALL {
+        assert_eq!(curve.name().parse::<Curve>(), Ok(*curve));
+        curve.visit(DispersalRoundTrip);
+    }
+    assert_eq!(Curve::NAMES.len(), Curve::ALL.len());
+    assert!("bls12_381".parse::<Curve>().is_err());
+}
+
+#[test]
+fn test_role_params_bls12_381() {
+    _test_role_params::<Bls12_381>()
+}
+
+#[test]
+fn test_role_params_bn254() {
+    _test_role_params::<Bn254>()
+}
+
+fn _test_role_params<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+
+    let scheme = SemiAvidPr::<E>::setup_with_segments(&mut rng, 16, 8, 64, 2);
+
+    // every role's parameters survive a round trip through their serialization
+    let mut bytes = Vec::new();
+    scheme.client_params().serialize(&mut bytes).unwrap();
+    let client = ClientParams::<E>::deserialize(&bytes[..]).unwrap();
+    assert_eq!(client, scheme.client_params());
+    let size_client = bytes.len();
+
+    let mut bytes = Vec::new();
+    scheme.node_params(5).serialize(&mut bytes).unwrap();
+    let node = NodeParams::<E>::deserialize(&bytes[..]).unwrap();
+    assert_eq!(node, scheme.node_params(5));
+    assert_eq!(node.get_idx(), 5);
+
+    let mut bytes = Vec::new();
+    scheme.sampler_params().serialize(&mut bytes).unwrap();
+    let sampler = SamplerParams::<E>::deserialize(&bytes[..]).unwrap();
+    let size_sampler = bytes.len();
+    assert!(size_sampler < 1024 && size_client > 2 * 64 * E::G1Affine::prime_subgroup_generator().serialized_size());
+
+    // the client disperses exactly as the full scheme does
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = client.disperse_compute_column_commitments(&data_uncoded);
+    assert_eq!(column_commitments, scheme.disperse_compute_column_commitments(&data_uncoded));
+    let data_coded = client.disperse_encode_rows(&data_uncoded);
+    assert_eq!(data_coded, scheme.disperse_encode_rows(&data_uncoded));
+
+    // each node checks only its own chunk
+    for idx in 0..16 {
+        let node = scheme.node_params(idx);
+        assert!(node.disperse_verify_chunk(&column_commitments, data_coded.column(idx)));
+        assert!(!node.disperse_verify_chunk(&column_commitments, data_coded.column((idx + 1) % 16)));
+    }
+    let mut chunk_tampered = data_coded.column(5).to_vec();
+    chunk_tampered[100] += E::Fr::one();
+    assert!(!node.disperse_verify_chunk(&column_commitments, &chunk_tampered));
+    assert!(!node.disperse_verify_chunk(&column_commitments[..8], data_coded.column(5)));
+
+    // the sampler checks openings of the client and of the full scheme
+    let openings = vec![
+        client.sampling_open_entry(&column_commitments, &data_uncoded, 70, 3),
+        scheme.sampling_open_entry(&column_commitments, &data_uncoded, 5, 7),
+    ];
+    assert_eq!(openings[0], scheme.sampling_open_entry(&column_commitments, &data_uncoded, 70, 3));
+    for opening in openings.iter() {
+        assert!(sampler.sampling_verify_entry(opening.clone()));
+    }
+    assert!(sampler.sampling_verify_entries(&mut rng, &openings));
+
+    let mut opening_tampered = openings[0].clone();
+    opening_tampered.0 += E::Fr::one();
+    assert!(!sampler.sampling_verify_entry(opening_tampered.clone()));
+    assert!(!sampler.sampling_verify_entries(&mut rng, &[openings[1].clone(), opening_tampered]));
+    let mut opening_out_of_range = openings[0].clone();
+    opening_out_of_range.1 = 128;
+    assert!(!sampler.sampling_verify_entry(opening_out_of_range));
+}
+
+#[test]
+fn test_shared_handle_bls12_381() {
+    _test_shared_handle::<Bls12_381>()
+}
+
+#[test]
+fn test_shared_handle_bn254() {
+    _test_shared_handle::<Bn254>()
+}
+
+fn _assert_handle<T: Clone + Send + Sync + 'static>() {}
+
+fn _test_shared_handle<E: PairingEngine>() {
+    _assert_handle::<SemiAvidPr<E>>();
+    _assert_handle::<SemiAvidPr<E, Pedersen<E::G1Affine>>>();
+
+    let mut rng = ark_std::rand::thread_rng();
+    let scheme = SemiAvidPr::<E>::setup(&mut rng, 16, 8, 32);
+
+    // concurrent dispersals and retrievals from the same nodes, each on its own clone
+    let idxs_download_nodes: Vec<usize> = vec![1, 2, 4, 5, 7, 8, 11, 14];
+    let threads: Vec<_> = (0..4).map(|_| {
+        let scheme = scheme.clone();
+        let idxs_download_nodes = idxs_download_nodes.clone();
+        std::thread::spawn(move || {
+            let mut rng = ark_std::rand::thread_rng();
+            let data_uncoded = scheme.generate_random_file(&mut rng);
+            let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+            let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+            assert!(scheme.disperse_verify_chunks(&column_commitments, &data_coded));
+
+            let data_coded_downloaded = scheme.retrieve_download_chunks(&data_coded, &idxs_download_nodes);
+            let decoder_aux = scheme.retrieve_prepare_decoding(&idxs_download_nodes);
+            assert_eq!(scheme.retrieve_decode_rows(&data_coded_downloaded, &decoder_aux), data_uncoded);
+            column_commitments
+        })
+    }).collect();
+    let results: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+    assert!(results.windows(2).all(|w| w[0] != w[1]));
+
+    // the clones share the setup and the decoder cache
+    let (hits, misses) = scheme.get_decoder_cache_stats();
+    assert_eq!((hits + misses, misses >= 1), (4, true));
+    let clone = scheme.clone();
+    assert!(Arc::ptr_eq(&scheme.commitment_scheme, &clone.commitment_scheme));
+    clone.retrieve_prepare_decoding(&idxs_download_nodes);
+    assert_eq!(scheme.get_decoder_cache_stats().0, hits + 1);
+}
+
+#[test]
+fn test_simulate_network_bls12_381() {
+    _test_simulate_network::<Bls12_381>()
+}
+
+#[test]
+fn test_simulate_network_bn254() {
+    _test_simulate_network::<Bn254>()
+}
+
+fn _test_simulate_network<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+    let scheme = SemiAvidPr::<E>::setup(&mut rng, 8, 4, 16);
+
+    // 10ms and 1MB/s on every link
+    let config = SimConfig::new(8, LinkConfig::new(0.01, 1e6));
+    let report = scheme.simulate_network(&mut StdRng::seed_from_u64(42), &config);
+
+    let size_entry = E::Fr::zero().serialized_size();
+    let size_commitments = 4 * E::G1Affine::prime_subgroup_generator().serialized_size();
+    let size_disperse = size_commitments + 16 * size_entry;
+    let size_control = std::mem::size_of::<usize>();
+    let size_signature = E::G1Affine::prime_subgroup_generator().serialized_size();
+    // bitmap of 8 signers, with its length
+    let size_certificate = 8 + 1 + size_signature;
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+    assert_eq!(report.num_acks, 8);
+    assert!(close(report.dispersal_latency_seconds.unwrap(), 0.02 + (size_disperse + size_signature) as f64 * 1e-6));
+    assert!(close(report.retrieval_latency_seconds.unwrap(), 0.02 + (size_control + 16 * size_entry + size_signature) as f64 * 1e-6));
+    assert!(report.sampling_latency_seconds.unwrap() > 0.03);
+    assert!(report.retrieved_correctly);
+    assert_eq!(report.idxs_retrieved_from.len(), 4);
+    assert_eq!((report.num_samples_passed, report.num_samples_failed), (8, 0));
+    assert!(report.certificate_verified);
+
+    for i in 0..8 {
+        assert_eq!(report.bytes_per_link[&(Actor::Client, Actor::Node(i))], size_disperse + size_control);
+        assert!(report.cpu_seconds[&Actor::Node(i)] > 0.0);
+    }
+    assert_eq!(report.bytes_sent(Actor::Client), 8 * (size_disperse + size_control) + size_commitments + size_certificate);
+    assert!(report.faults.is_empty());
+    assert!(report.cpu_seconds[&Actor::Client] > 0.0 && report.cpu_seconds[&Actor::Sampler] > 0.0);
+
+    // the schedule is deterministic, only the measured CPU times differ
+    let mut report2 = scheme.simulate_network(&mut StdRng::seed_from_u64(42), &config);
+    report2.cpu_seconds = report.cpu_seconds.clone();
+    assert_eq!(report2, report);
+
+    // a node behind a slow link delays the dispersal unless the client does not wait for it
+    let mut config = SimConfig::new(8, LinkConfig::new(0.01, 1e6));
+    config.set_link(Actor::Client, Actor::Node(3), LinkConfig::new(1.0, 1e6));
+    let report = scheme.simulate_network(&mut rng, &config);
+    assert!(report.dispersal_latency_seconds.unwrap() > 1.0);
+    config.num_acks = 7;
+    let report = scheme.simulate_network(&mut rng, &config);
+    assert!(report.dispersal_latency_seconds.unwrap() < 0.1);
+    assert!(report.retrieved_correctly && !report.idxs_retrieved_from.contains(&3));
+
+    // charging computation only delays
+    config.charge_cpu_time = true;
+    let report_charged = scheme.simulate_network(&mut rng, &config);
+    assert!(report_charged.dispersal_latency_seconds.unwrap() > report.dispersal_latency_seconds.unwrap());
+    assert!(report_charged.retrieved_correctly);
+}
+
+
+#[test]
+fn test_simulate_byzantine_bls12_381() {
+    _test_simulate_byzantine::<Bls12_381>()
+}
+
+#[test]
+fn test_simulate_byzantine_bn254() {
+    _test_simulate_byzantine::<Bn254>()
+}
+
+fn _test_simulate_byzantine<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+    let scheme = SemiAvidPr::<E>::setup(&mut rng, 8, 4, 16);
+
+    // honest nodes reject inconsistent chunks
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+    let mut columns: Vec<Vec<E::Fr>> = data_coded.columns().map(|c| c.to_vec()).collect();
+    columns[2][5] += E::Fr::one();
+    let data_coded_inconsistent = FileMatrix::from_columns(data_coded.height(), data_coded.width(), columns.clone());
+    assert!(scheme.disperse_verify_chunks(&column_commitments, &data_coded));
+    assert!(!scheme.disperse_verify_chunks(&column_commitments, &data_coded_inconsistent));
+    for (i, column) in columns.iter().enumerate() {
+        assert_eq!(scheme.disperse_verify_chunk(&column_commitments, column, i), i != 2);
+    }
+
+    // ... also in the simulation, where the client then never collects all acknowledgements
+    let mut config = SimConfig::new(8, LinkConfig::new(0.01, 1e6));
+    config.client_behavior = ClientBehavior::InconsistentChunks([2, 6].into_iter().collect());
+    let report = scheme.simulate_network(&mut StdRng::seed_from_u64(42), &config);
+    assert_eq!(report.num_acks, 6);
+    assert_eq!(report.dispersal_latency_seconds, None);
+    assert_eq!(report.faults, [(Actor::Client, Fault::InconsistentChunk(2)), (Actor::Client, Fault::InconsistentChunk(6))].into_iter().collect());
+
+    config.num_acks = 6;
+    let report = scheme.simulate_network(&mut StdRng::seed_from_u64(42), &config);
+    assert!(report.retrieved_correctly && report.num_samples_failed == 0);
+    assert!(!report.idxs_retrieved_from.contains(&2) && !report.idxs_retrieved_from.contains(&6));
+
+    // with k = 4 honest nodes left, retrieval succeeds and each faulty node is reported
+    let mut config = SimConfig::new(8, LinkConfig::new(0.01, 1e6));
+    config.num_samples = 64;
+    config.with_reader = true;
+    config.set_node_behavior(0, NodeBehavior::Withhold);
+    config.set_node_behavior(1, NodeBehavior::Corrupt);
+    config.set_node_behavior(3, NodeBehavior::Equivocate);
+    config.set_node_behavior(6, NodeBehavior::Corrupt);
+    let report = scheme.simulate_network(&mut StdRng::seed_from_u64(42), &config);
+    assert_eq!(report.num_acks, 8);
+    assert!(report.retrieved_correctly && report.reader_retrieved_correctly);
+    // the equivocating node answers the client, which asks first, with its valid chunk ...
+    let mut idxs_retrieved_from = report.idxs_retrieved_from.clone();
+    idxs_retrieved_from.sort();
+    assert_eq!(idxs_retrieved_from, vec![2, 3, 4, 5]);
+    // ... and the reader with another signed one, which convicts it of equivocation, while
+    // the corrupting nodes return the same invalid chunk to both
+    assert_eq!(report.faults, [
+        (Actor::Node(0), Fault::Withheld),
+        (Actor::Node(1), Fault::InvalidChunk),
+        (Actor::Node(1), Fault::FailedSample),
+        (Actor::Node(3), Fault::InvalidChunk),
+        (Actor::Node(3), Fault::Equivocation),
+        (Actor::Node(6), Fault::InvalidChunk),
+        (Actor::Node(6), Fault::FailedSample),
+    ].into_iter().collect());
+    assert!(report.num_samples_failed > 0);
+    // the sampler waits for the withholding node forever
+    assert_eq!(report.sampling_latency_seconds, None);
+
+    // with a single retrieving party, the equivocating node looks honest
+    config.with_reader = false;
+    let report = scheme.simulate_network(&mut StdRng::seed_from_u64(42), &config);
+    assert!(report.retrieved_correctly && !report.reader_retrieved_correctly);
+    assert!(!report.faulty_actors().contains(&Actor::Node(3)));
+
+    // with fewer than k honest nodes, retrieval fails, but not silently
+    config.set_node_behavior(3, NodeBehavior::Corrupt);
+    config.set_node_behavior(7, NodeBehavior::Withhold);
+    let report = scheme.simulate_network(&mut StdRng::seed_from_u64(42), &config);
+    assert!(!report.retrieved_correctly);
+    assert_eq!(report.retrieval_latency_seconds, None);
+    assert_eq!(report.idxs_retrieved_from, Vec::<usize>::new());
+    assert!(report.faults.contains(&(Actor::Node(7), Fault::Withheld)));
+}
+
+
+#[test]
+fn test_dispersal_certificate_bls12_381() {
+    _test_dispersal_certificate::<Bls12_381>()
+}
+
+#[test]
+fn test_dispersal_certificate_bn254() {
+    _test_dispersal_certificate::<Bn254>()
+}
+
+fn _test_dispersal_certificate<E: PairingEngine>() {
+    let mut rng = ark_std::rand::thread_rng();
+    let scheme = SemiAvidPr::<E>::setup(&mut rng, 8, 4, 16);
+    let quorum = scheme.certificate_quorum(2);
+    assert_eq!(quorum, 6);
+
+    let signing_keys: Vec<SigningKey<E>> = (0..8).map(|_| SigningKey::generate(&mut rng)).collect();
+    let mut registry = NodeRegistry::new();
+    for (i, signing_key) in signing_keys.iter().enumerate() {
+        assert_eq!(registry.register(signing_key.public_key(), &signing_key.prove_possession()), Some(i));
+    }
+
+    // keys without proof of possession, e.g., rogue keys, are not registered
+    let rogue_key = SigningKey::<E>::generate(&mut rng);
+    assert_eq!(registry.clone().register(rogue_key.public_key(), &signing_keys[0].prove_possession()), None);
+    assert_eq!(registry.clone().register(rogue_key.public_key(), &rogue_key.sign(&FileId([0; 64]))), None);
+
+    let data_uncoded = scheme.generate_random_file(&mut rng);
+    let column_commitments = scheme.disperse_compute_column_commitments(&data_uncoded);
+    let data_coded = scheme.disperse_encode_rows(&data_uncoded);
+    let file_id = scheme.file_id(&column_commitments);
+
+    // nodes sign only valid chunks; node 7 receives an inconsistent one
+    let mut signatures = Vec::new();
+    for (i, signing_key) in signing_keys.iter().enumerate() {
+        let mut chunk = data_coded.column(i).to_vec();
+        if i == 7 {
+            chunk[0] += E::Fr::one();
+        }
+        match scheme.disperse_sign_chunk(signing_key, &column_commitments, &chunk, i) {
+            Some(signature) => {
+                assert!(registry.public_key(i).verify(&file_id, &signature));
+                signatures.push((i, signature));
+            },
+            None => assert_eq!(i, 7),
+        }
+    }
+    assert_eq!(signatures.len(), 7);
+
+    let certificate = DispersalCertificate::aggregate(&registry, &file_id, &signatures[..6], quorum).unwrap();
+    assert_eq!(certificate.signers(), vec![0, 1, 2, 3, 4, 5]);
+    assert!(certificate.verify(&registry, &file_id, quorum));
+    assert_eq!(certificate.serialized_size(), 8 + 1 + E::G1Affine::prime_subgroup_generator().serialized_size());
+    let mut bytes = Vec::new();
+    certificate.serialize(&mut bytes).unwrap();
+    assert_eq!(DispersalCertificate::<E>::deserialize(&bytes[..]).unwrap(), certificate);
+
+    // not for another file, nor a higher quorum, nor another registry
+    let other_file_id = scheme.file_id(&scheme.disperse_compute_column_commitments(&scheme.generate_random_file(&mut rng)));
+    assert!(!certificate.verify(&registry, &other_file_id, quorum));
+    assert!(!certificate.verify(&registry, &file_id, 7));
+    let mut other_registry = NodeRegistry::new();
+    for signing_key in signing_keys.iter().rev() {
+        other_registry.register(signing_key.public_key(), &signing_key.prove_possession()).unwrap();
+    }
+    assert!(!certificate.verify(&other_registry, &file_id, quorum));
+
+    // invalid, duplicate and unregistered signatures do not count towards the quorum
+    let mut signatures_bad = signatures[..5].to_vec();
+    signatures_bad.push((5, signing_keys[5].sign(&other_file_id)));
+    signatures_bad.push((6, signatures[0].1));
+    signatures_bad.push(signatures[4]);
+    signatures_bad.push((8, rogue_key.sign(&file_id)));
+    assert_eq!(DispersalCertificate::aggregate(&registry, &file_id, &signatures_bad, quorum), None);
+    signatures_bad.push(signatures[6]);
+    let certificate = DispersalCertificate::aggregate(&registry, &file_id, &signatures_bad, quorum).unwrap();
+    assert_eq!(certificate.signers(), vec![0, 1, 2, 3, 4, 6]);
+    assert!(certificate.verify(&registry, &file_id, quorum));
 }
@@ -0,0 +1,80 @@
+use ark_ff::fields::{Field, PrimeField};
+use ark_ec::{ProjectiveCurve, AffineCurve, PairingEngine};
+use ark_poly::{EvaluationDomain};
+use ark_std::{start_timer, end_timer};
+
+use crate::{SemiAvidPr};
+
+
+/// Modification of a single entry of a dispersed file, as sent by the client to all storage nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EntryUpdate<F: Field> {
+    pub row: usize,
+    pub col: usize,
+    pub delta: F,
+}
+
+
+impl<E: PairingEngine> SemiAvidPr<E> {
+    fn lagrange_delta(&self, update: &EntryUpdate<E::Fr>) -> E::G1Projective {
+        self.commitment_scheme.ck_lagrange[update.row % self.L].mul(update.delta.into_repr())
+    }
+
+    fn coded_delta(&self, update: &EntryUpdate<E::Fr>, idx: usize) -> E::Fr {
+        update.delta * self.domain_encoding.element(idx).pow([update.col as u64])
+    }
+
+
+    /// Change entry `(row, col)` of the file from `old` to `new`, returning the updated column
+    /// commitments (only one of which differs, by a multiple of a single Lagrange-basis SRS
+    /// element) and the update to be sent to the storage nodes.
+    pub fn update_entry(&self, column_commitments: &[E::G1Affine], row: usize, col: usize, old: E::Fr, new: E::Fr) -> (Vec<E::G1Affine>, EntryUpdate<E::Fr>) {
+        assert!(row < self.get_num_rows());
+        assert!(col < self.k);
+
+        let update = EntryUpdate { row, col, delta: new - old };
+
+        let timer = start_timer!(|| "Updating column commitment");
+        let mut column_commitments = column_commitments.to_vec();
+        let idx = (row / self.L) * self.k + col;
+        column_commitments[idx] = (column_commitments[idx].into_projective() + self.lagrange_delta(&update)).into_affine();
+        end_timer!(timer);
+
+        (column_commitments, update)
+    }
+
+
+    /// Change of entry `update.row` of every coded chunk, i.e., of row `update.row` of the coded file.
+    pub fn update_coded_deltas(&self, update: &EntryUpdate<E::Fr>) -> Vec<E::Fr> {
+        (0..self.n).map(|idx| self.coded_delta(update, idx)).collect()
+    }
+
+
+    /// Check at storage node `idx` that the new column commitments are the old ones modified
+    /// according to `update`, and if so apply the update to the node's coded chunk `chunk`.
+    /// Costs one scalar multiplication, independent of the chunk size.
+    pub fn update_verify_and_apply(&self, column_commitments_old: &[E::G1Affine], column_commitments_new: &[E::G1Affine], update: &EntryUpdate<E::Fr>, chunk: &mut [E::Fr], idx: usize) -> bool {
+        if column_commitments_old.len() != self.get_num_column_commitments() || column_commitments_new.len() != column_commitments_old.len()
+            || chunk.len() != self.get_num_rows() || idx >= self.n || update.row >= self.get_num_rows() || update.col >= self.k {
+            return false;
+        }
+
+        let timer = start_timer!(|| "Checking updated column commitments");
+        let idx_updated = (update.row / self.L) * self.k + update.col;
+        for (i, (c_old, c_new)) in column_commitments_old.iter().zip(column_commitments_new.iter()).enumerate() {
+            let c_expected = if i == idx_updated {
+                (c_old.into_projective() + self.lagrange_delta(update)).into_affine()
+            } else {
+                *c_old
+            };
+            if *c_new != c_expected {
+                return false;
+            }
+        }
+        end_timer!(timer);
+
+        chunk[update.row] += self.coded_delta(update, idx);
+
+        true
+    }
+}
@@ -1,6 +1,6 @@
 use ark_ff::fields::{Field};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Matrix<T: Field> {
     entries: Vec<T>,
     myheight: usize,
@@ -42,6 +42,14 @@ impl<T: Field> Matrix<T> {
         matrix
     }
 
+    pub fn zeros(height: usize, width: usize) -> Self {
+        Self { entries: vec![T::zero(); height*width], myheight: height, mywidth: width }
+    }
+
+    pub fn identity(height: usize) -> Self {
+        Self::from_diagonal_element(height, T::zero(), T::one())
+    }
+
     fn from_diagonal_element(height: usize, off_diag_elem: T, on_diag_elem: T) -> Self {
         let mut entries = Vec::new();
         entries.resize(height*height, off_diag_elem);
@@ -54,7 +62,7 @@ impl<T: Field> Matrix<T> {
         matrix
     }
 
-    fn hcat(&self, other: &Self) -> Self {
+    pub fn hcat(&self, other: &Self) -> Self {
         assert!(self.height() == other.height());
 
         let height = self.height();
@@ -76,7 +84,7 @@ impl<T: Field> Matrix<T> {
         matrix
     }
 
-    fn hpick(&self, idxs: &[usize]) -> Self {
+    pub fn hpick(&self, idxs: &[usize]) -> Self {
         let height = self.height();
         let width = idxs.len();
 
@@ -93,6 +101,81 @@ impl<T: Field> Matrix<T> {
         matrix
     }
 
+    pub fn vpick(&self, idxs: &[usize]) -> Self {
+        let mut entries = Vec::with_capacity(idxs.len()*self.width());
+        for &row in idxs {
+            entries.extend_from_slice(self.row(row));
+        }
+
+        Self { entries, myheight: idxs.len(), mywidth: self.mywidth }
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.entries[row*self.mywidth..(row+1)*self.mywidth]
+    }
+
+    pub fn column(&self, col: usize) -> impl ExactSizeIterator<Item = &T> + '_ {
+        assert!(col < self.mywidth);
+        self.entries[col..].iter().step_by(self.mywidth)
+    }
+
+    /// Copy of the submatrix of the given ranges of rows and columns.
+    pub fn slice(&self, rows: core::ops::Range<usize>, cols: core::ops::Range<usize>) -> Self {
+        assert!(rows.end <= self.height() && cols.end <= self.width());
+
+        let mut entries = Vec::with_capacity(rows.len()*cols.len());
+        for row in rows.clone() {
+            entries.extend_from_slice(&self.row(row)[cols.clone()]);
+        }
+
+        Self { entries, myheight: rows.len(), mywidth: cols.len() }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut entries = Vec::with_capacity(self.entries.len());
+        for col in 0..self.width() {
+            entries.extend(self.column(col));
+        }
+
+        Self { entries, myheight: self.mywidth, mywidth: self.myheight }
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        assert!(self.width() == other.height());
+
+        // accumulate scaled rows of `other`, so that all accesses are sequential
+        let mut matrix = Self::zeros(self.height(), other.width());
+        for row in 0..self.height() {
+            let row_dst = &mut matrix.entries[row*other.mywidth..(row+1)*other.mywidth];
+            for (i, factor) in self.row(row).iter().enumerate() {
+                if !factor.is_zero() {
+                    for (dst, src) in row_dst.iter_mut().zip(other.row(i)) {
+                        *dst += *factor * src;
+                    }
+                }
+            }
+        }
+
+        matrix
+    }
+
+    pub fn mul_vec(&self, vec: &[T]) -> Vec<T> {
+        assert!(self.width() == vec.len());
+        (0..self.height()).map(|row| self.row(row).iter().zip(vec).map(|(a, b)| *a * b).sum()).collect()
+    }
+
+    fn swap_rows(&mut self, row1: usize, row2: usize) {
+        if row1 != row2 {
+            for col in 0..self.width() {
+                self.entries.swap(row1 * self.mywidth + col, row2 * self.mywidth + col);
+            }
+        }
+    }
+
+    fn find_pivot_row(&self, col: usize, row_start: usize) -> Option<usize> {
+        (row_start..self.height()).find(|&row| !self.get(row, col).is_zero())
+    }
+
     fn divide_row(&mut self, row: usize, val: T) {
         for col in 0..self.width() {
             self.set(row, col, self.get(row, col) / val);
@@ -105,16 +188,18 @@ impl<T: Field> Matrix<T> {
         }
     }
 
-    pub fn invert(&self) -> Self {
+    /// Inverse by Gauss-Jordan elimination with row swaps, so that only singular matrices fail.
+    pub fn invert(&self) -> Result<Self, SingularMatrix> {
         assert!(self.width() == self.height());
 
         let d = self.height();
         let mut matrix = self.hcat(&Matrix::from_diagonal_element(d, T::zero(), T::one()));
 
         for i in 0..d {
-            let pivot = matrix.get(i, i);
-            assert!(!pivot.is_zero());
+            let pivot_row = matrix.find_pivot_row(i, i).ok_or(SingularMatrix)?;
+            matrix.swap_rows(i, pivot_row);
 
+            let pivot = matrix.get(i, i);
             matrix.divide_row(i, pivot);
 
             for j in 0..d {
@@ -124,6 +209,338 @@ impl<T: Field> Matrix<T> {
             }
         }
     
-        matrix.hpick(&(d..2*d).collect::<Vec<usize>>())
+        Ok(matrix.hpick(&(d..2*d).collect::<Vec<usize>>()))
+    }
+
+    /// LU factorization with row pivoting, `P * self = L * U`, to be reused for several solves.
+    pub fn lu(&self) -> Result<LuDecomposition<T>, SingularMatrix> {
+        assert!(self.width() == self.height());
+
+        let d = self.height();
+        let mut lu = self.clone();
+        let mut perm: Vec<usize> = (0..d).collect();
+        let mut num_swaps = 0;
+
+        for i in 0..d {
+            let pivot_row = lu.find_pivot_row(i, i).ok_or(SingularMatrix)?;
+            if pivot_row != i {
+                lu.swap_rows(i, pivot_row);
+                perm.swap(i, pivot_row);
+                num_swaps += 1;
+            }
+
+            let pivot_inv = lu.get(i, i).inverse().unwrap();
+            for j in (i+1)..d {
+                let factor = lu.get(j, i) * pivot_inv;
+                lu.set(j, i, factor);
+                for col in (i+1)..d {
+                    lu.set(j, col, lu.get(j, col) - factor * lu.get(i, col));
+                }
+            }
+        }
+
+        Ok(LuDecomposition { lu, perm, num_swaps })
+    }
+
+    pub fn solve(&self, rhs: &[T]) -> Result<Vec<T>, SingularMatrix> {
+        Ok(self.lu()?.solve(rhs))
+    }
+
+    /// Solve for every column of `rhs` as right-hand side.
+    pub fn solve_many(&self, rhs: &Self) -> Result<Self, SingularMatrix> {
+        Ok(self.lu()?.solve_many(rhs))
+    }
+
+    pub fn rank(&self) -> usize {
+        let mut matrix = self.clone();
+        let mut rank = 0;
+
+        for col in 0..self.width() {
+            if let Some(pivot_row) = matrix.find_pivot_row(col, rank) {
+                matrix.swap_rows(rank, pivot_row);
+                let pivot_inv = matrix.get(rank, col).inverse().unwrap();
+                for j in (rank+1)..self.height() {
+                    matrix.multiply_row_and_add_to_row(rank, -matrix.get(j, col) * pivot_inv, j);
+                }
+                rank += 1;
+            }
+        }
+
+        rank
+    }
+
+    pub fn determinant(&self) -> T {
+        match self.lu() {
+            Ok(lu) => lu.determinant(),
+            Err(SingularMatrix) => T::zero(),
+        }
+    }
+}
+
+
+impl<T: Field> core::ops::Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(col < self.mywidth);
+        &self.entries[row*self.mywidth + col]
+    }
+}
+
+impl<T: Field> core::ops::IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(col < self.mywidth);
+        &mut self.entries[row*self.mywidth + col]
+    }
+}
+
+
+/// Error for operations that require an invertible matrix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SingularMatrix;
+
+impl core::fmt::Display for SingularMatrix {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "matrix is singular")
+    }
+}
+
+impl std::error::Error for SingularMatrix {}
+
+
+/// Result of `Matrix::lu`: unit lower triangular `L` and upper triangular `U` stored in place,
+/// together with the row permutation `P`.
+#[derive(Clone, Debug)]
+pub struct LuDecomposition<T: Field> {
+    lu: Matrix<T>,
+    perm: Vec<usize>,
+    num_swaps: usize,
+}
+
+impl<T: Field> LuDecomposition<T> {
+    pub fn solve(&self, rhs: &[T]) -> Vec<T> {
+        let d = self.lu.height();
+        assert!(rhs.len() == d);
+
+        // forward substitution with L (unit diagonal)
+        let mut x: Vec<T> = self.perm.iter().map(|&i| rhs[i]).collect();
+        for row in 0..d {
+            for col in 0..row {
+                let tmp = self.lu.get(row, col) * x[col];
+                x[row] -= tmp;
+            }
+        }
+
+        // backward substitution with U
+        for row in (0..d).rev() {
+            for col in (row+1)..d {
+                let tmp = self.lu.get(row, col) * x[col];
+                x[row] -= tmp;
+            }
+            x[row] /= self.lu.get(row, row);
+        }
+
+        x
+    }
+
+    pub fn solve_many(&self, rhs: &Matrix<T>) -> Matrix<T> {
+        assert!(rhs.height() == self.lu.height());
+
+        let mut matrix = rhs.clone();
+        for col in 0..rhs.width() {
+            let x = self.solve(&(0..rhs.height()).map(|row| rhs.get(row, col)).collect::<Vec<T>>());
+            for (row, v) in x.into_iter().enumerate() {
+                matrix.set(row, col, v);
+            }
+        }
+
+        matrix
+    }
+
+    pub fn determinant(&self) -> T {
+        let det: T = (0..self.lu.height()).map(|i| self.lu.get(i, i)).product();
+        if self.num_swaps % 2 == 1 { -det } else { det }
+    }
+
+    pub fn inverse(&self) -> Matrix<T> {
+        self.solve_many(&Matrix::from_diagonal_element(self.lu.height(), T::zero(), T::one()))
+    }
+}
+
+
+/// Coefficients (lowest degree first) of the monic polynomial `prod_j (x - points[j])`.
+pub fn vanishing_polynomial<T: Field>(points: &[T]) -> Vec<T> {
+    let mut coeffs = vec![T::zero(); points.len() + 1];
+    coeffs[0] = T::one();
+    for (deg, point) in points.iter().enumerate() {
+        for i in (1..=deg+1).rev() {
+            coeffs[i] = coeffs[i-1] - *point * coeffs[i];
+        }
+        coeffs[0] = -*point * coeffs[0];
+    }
+
+    coeffs
+}
+
+
+/// Inverse of the Vandermonde matrix `V` with `V[i][j] = points[j]^i` in O(d^2), via Lagrange
+/// interpolation: row `j` of the inverse holds the coefficients of the Lagrange basis polynomial
+/// of `points[j]`, `prod_{m != j} (x - points[m]) / (points[j] - points[m])`.
+pub fn vandermonde_inverse<T: Field>(points: &[T]) -> Result<Matrix<T>, SingularMatrix> {
+    let d = points.len();
+    let master = vanishing_polynomial(points);
+
+    let mut matrix = Matrix { entries: vec![T::zero(); d*d], myheight: d, mywidth: d };
+    let mut quotient = vec![T::zero(); d];
+    for (j, point) in points.iter().enumerate() {
+        // synthetic division of the master polynomial by (x - points[j])
+        let mut carry = T::zero();
+        for i in (0..d).rev() {
+            carry = master[i+1] + *point * carry;
+            quotient[i] = carry;
+        }
+
+        // barycentric weight, i.e., the quotient evaluated at points[j]
+        let weight = quotient.iter().rev().fold(T::zero(), |acc, c| acc * point + c);
+        let weight_inv = weight.inverse().ok_or(SingularMatrix)?;
+
+        for (i, c) in quotient.iter().enumerate() {
+            matrix.set(j, i, *c * weight_inv);
+        }
+    }
+
+    Ok(matrix)
+}
+
+
+/// Contiguous column-major storage of a file (or coded file), so that the columns that get
+/// committed to and stored by nodes are slices, while rows are cheap strided views.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileMatrix<T: Field> {
+    entries: Vec<T>,
+    myheight: usize,
+    mywidth: usize,
+}
+
+impl<T: Field> FileMatrix<T> {
+    pub fn height(&self) -> usize {
+        self.myheight
+    }
+
+    pub fn width(&self) -> usize {
+        self.mywidth
+    }
+
+    pub fn zeros(height: usize, width: usize) -> Self {
+        Self { entries: vec![T::zero(); height*width], myheight: height, mywidth: width }
+    }
+
+    pub fn from_columns(height: usize, width: usize, columns: Vec<Vec<T>>) -> Self {
+        assert!(columns.len() == width);
+        let mut entries = Vec::with_capacity(height*width);
+        for column in columns {
+            assert!(column.len() == height);
+            entries.extend(column);
+        }
+
+        Self { entries, myheight: height, mywidth: width }
+    }
+
+    pub fn from_rows(height: usize, width: usize, rows: &[Vec<T>]) -> Self {
+        assert!(rows.len() == height);
+        let mut matrix = Self::zeros(height, width);
+        for (row, vals) in rows.iter().enumerate() {
+            assert!(vals.len() == width);
+            for (col, val) in vals.iter().enumerate() {
+                matrix[(row, col)] = *val;
+            }
+        }
+
+        matrix
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        (0..self.height()).map(|row| self.row(row).copied().collect()).collect()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> T {
+        self[(row, col)]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, val: T) {
+        self[(row, col)] = val;
+    }
+
+    pub fn column(&self, col: usize) -> &[T] {
+        &self.entries[col*self.myheight..(col+1)*self.myheight]
+    }
+
+    pub fn column_mut(&mut self, col: usize) -> &mut [T] {
+        &mut self.entries[col*self.myheight..(col+1)*self.myheight]
+    }
+
+    pub fn columns(&self) -> impl ExactSizeIterator<Item = &[T]> + '_ {
+        (0..self.mywidth).map(move |col| self.column(col))
+    }
+
+    pub fn row(&self, row: usize) -> impl ExactSizeIterator<Item = &T> + '_ {
+        assert!(row < self.myheight);
+        self.entries[row..].iter().step_by(self.myheight)
+    }
+
+    /// Copy of the given columns, in the given order (e.g., the chunks downloaded from a set of nodes).
+    pub fn pick_columns(&self, idxs: &[usize]) -> Self {
+        let mut entries = Vec::with_capacity(self.myheight*idxs.len());
+        for &col in idxs {
+            entries.extend_from_slice(self.column(col));
+        }
+
+        Self { entries, myheight: self.myheight, mywidth: idxs.len() }
+    }
+
+    /// Product with `other`, computing every column of the result as a linear combination of
+    /// the (contiguous) columns of `self`.
+    pub fn mul(&self, other: &Matrix<T>) -> Self {
+        assert!(self.width() == other.height());
+
+        let mut matrix = Self::zeros(self.height(), other.width());
+        for col in 0..other.width() {
+            let column_dst = matrix.column_mut(col);
+            for (i, column_src) in self.columns().enumerate() {
+                let factor = other.get(i, col);
+                if !factor.is_zero() {
+                    for (dst, src) in column_dst.iter_mut().zip(column_src) {
+                        *dst += factor * src;
+                    }
+                }
+            }
+        }
+
+        matrix
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut entries = Vec::with_capacity(self.entries.len());
+        for row in 0..self.myheight {
+            entries.extend(self.row(row));
+        }
+
+        Self { entries, myheight: self.mywidth, mywidth: self.myheight }
+    }
+}
+
+impl<T: Field> core::ops::Index<(usize, usize)> for FileMatrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.myheight);
+        &self.entries[col*self.myheight + row]
+    }
+}
+
+impl<T: Field> core::ops::IndexMut<(usize, usize)> for FileMatrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.myheight);
+        &mut self.entries[col*self.myheight + row]
     }
 }